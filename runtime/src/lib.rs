@@ -0,0 +1,64 @@
+//! Runtime companion of `actix-web-cute-codegen`
+//!
+//! Traits, which are implemented by services generated by its macros.
+
+///Routes of service: full path, methods and name of handler.
+///
+///Empty methods mean that handler accepts any method.
+pub type Routes = Vec<(String, &'static [&'static str], &'static str)>;
+
+///Metadata of route, implemented by every service generated by route macros (e.g. `#[get]`).
+pub trait RouteInfo {
+    ///Name of handler.
+    const NAME: &'static str;
+    ///Path of route, relative to its scope.
+    const PATH: &'static str;
+    ///Methods of route, empty for any method.
+    const METHODS: &'static [&'static str];
+    ///Guards of route, except method guard.
+    const GUARDS: &'static [&'static str];
+    ///Doc comment of handler.
+    const DOC: &'static str;
+}
+
+///Routes of scope, implemented by services generated by `#[scope]` and `#[derive(Scope)]`.
+pub trait ScopeInfo {
+    ///Returns routes of scope with full paths, including ones of nested scopes and handlers.
+    fn routes() -> Routes;
+}
+
+///Records of routes, which are reached by requests of generated `smoke_test()`.
+///
+///Every route registers guard, which records its name, within tests only.
+pub mod smoke {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    ///Header with token of smoke test's request.
+    pub const HEADER: &str = "x-smoke-test";
+
+    static NEXT_TOKEN: AtomicUsize = AtomicUsize::new(0);
+    static RECORDS: Mutex<Vec<(String, &'static str)>> = Mutex::new(Vec::new());
+
+    ///Returns unique token of request.
+    pub fn token() -> String {
+        format!("{}", NEXT_TOKEN.fetch_add(1, Ordering::Relaxed))
+    }
+
+    ///Records that request with `token` reaches route `name`, if token is present.
+    ///
+    ///Always returns `true`, so that it can be used as guard.
+    pub fn record(token: Option<&str>, name: &'static str) -> bool {
+        if let Some(token) = token {
+            RECORDS.lock().unwrap_or_else(|error| error.into_inner()).push((token.to_owned(), name));
+        }
+        true
+    }
+
+    ///Returns name of route, which is reached by request with `token`.
+    pub fn take(token: &str) -> Option<&'static str> {
+        let mut records = RECORDS.lock().unwrap_or_else(|error| error.into_inner());
+        let idx = records.iter().position(|(record, _)| record == token)?;
+        Some(records.remove(idx).1)
+    }
+}
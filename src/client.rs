@@ -0,0 +1,281 @@
+use std::fmt;
+
+use crate::openapi::extractor;
+use crate::route::{path_parameters, GuardType};
+
+///Error type of client's futures.
+const ERROR: &str = "actix_web::Error";
+
+///Method of generated client, which calls route.
+pub struct Method {
+    name: String,
+    method: &'static str,
+    ///Path with `{..}` segments replaced by `{}`, to be used as format string.
+    format: String,
+    ///Names and types of path parameters.
+    parameters: Vec<(String, String)>,
+    ///Type of query, serialized with `serde_urlencoded`.
+    query: Option<String>,
+    ///Name of awc's send method (e.g. `send_json`) and type of body.
+    body: Option<(&'static str, String)>,
+    ///Type of JSON response.
+    response: Option<String>,
+}
+
+///Returns identifier for path parameter, falling back to its position.
+fn parameter_name(name: &str, idx: usize) -> String {
+    match syn::parse_str::<syn::Ident>(name) {
+        Ok(_) => name.to_owned(),
+        Err(_) => format!("param{}", idx),
+    }
+}
+
+impl Method {
+    ///Creates method for route's handler, unless route accepts any method.
+    ///
+    ///Path parameters are typed by `Path` extractor, when it is single type or tuple of matching length,
+    ///otherwise they accept anything that implements `Display`.
+    pub fn new(fun: &syn::ItemFn, path: &str, guard: &GuardType) -> Option<Self> {
+        let method = guard.method()?;
+        let names = path_parameters(path);
+
+        let mut format = String::new();
+        let mut rest = path;
+        while let Some(start) = rest.find('{') {
+            let end = match rest[start..].find('}') {
+                Some(end) => start + end,
+                None => break,
+            };
+            format.push_str(&rest[..start]);
+            format.push_str("{}");
+            rest = &rest[end+1..];
+        }
+        format.push_str(rest);
+
+        let mut types = Vec::new();
+        let mut query = None;
+        let mut body = None;
+        for arg in fun.decl.inputs.iter() {
+            let typ = match arg {
+                syn::FnArg::Captured(arg) => &arg.ty,
+                _ => continue,
+            };
+
+            match extractor(typ) {
+                Some((ref name, ref inner)) if name == "Path" => types = match syn::parse_str::<syn::Type>(inner) {
+                    Ok(syn::Type::Tuple(ref tuple)) if tuple.elems.len() == names.len() => tuple.elems.iter().map(|typ| quote::quote!(#typ).to_string()).collect(),
+                    Ok(_) if names.len() == 1 => vec![inner.clone()],
+                    _ => Vec::new(),
+                },
+                Some((ref name, inner)) if name == "Query" => query = Some(inner),
+                Some((ref name, inner)) if name == "Json" => body = Some(("send_json", inner)),
+                Some((ref name, inner)) if name == "Form" => body = Some(("send_form", inner)),
+                _ => continue,
+            }
+        }
+
+        let parameters = names.iter().enumerate().map(|(idx, name)| {
+            let typ = types.get(idx).cloned().unwrap_or_else(|| "impl std::fmt::Display".to_owned());
+            (parameter_name(name, idx), typ)
+        }).collect();
+
+        let response = match fun.decl.output {
+            syn::ReturnType::Type(_, ref typ) => match extractor(typ) {
+                Some((ref name, inner)) if name == "Json" => Some(inner),
+                _ => None,
+            },
+            syn::ReturnType::Default => None,
+        };
+
+        Some(Self {
+            name: fun.ident.to_string(),
+            method,
+            format,
+            parameters,
+            query,
+            body,
+            response,
+        })
+    }
+
+    ///Writes signature of method, which takes parameters of scope's path (`scope_parameters`) before its own.
+    fn write_signature(&self, f: &mut fmt::Formatter, scope_parameters: &[String]) -> fmt::Result {
+        let item = match self.response {
+            Some(ref response) => response.clone(),
+            None => "(actix_web::http::StatusCode, Vec<u8>)".to_owned(),
+        };
+
+        write!(f, "fn {}(&self", self.name)?;
+        for name in scope_parameters.iter() {
+            write!(f, ", {}: impl std::fmt::Display", name)?;
+        }
+        for (name, typ) in self.parameters.iter() {
+            write!(f, ", {}: {}", name, typ)?;
+        }
+        if let Some(ref query) = self.query {
+            write!(f, ", query: &{}", query)?;
+        }
+        if let Some((_, ref body)) = self.body {
+            write!(f, ", body: &{}", body)?;
+        }
+        write!(f, ") -> Box<dyn futures::Future<Item={}, Error={}>>", item, ERROR)
+    }
+
+    ///Writes method of client.
+    ///
+    ///JSON responses are decoded, while others are returned as status and body.
+    ///Path parameters are percent-encoded.
+    fn write(&self, f: &mut fmt::Formatter, vis: &str, scope_parameters: &[String]) -> fmt::Result {
+        write!(f, "    {}", vis)?;
+        self.write_signature(f, scope_parameters)?;
+        writeln!(f, " {{")?;
+
+        write!(f, "        let url = format!(\"{{}}{{}}{}\", self.url, self.scope_path(&[", self.format)?;
+        for name in scope_parameters.iter() {
+            write!(f, "&{}, ", name)?;
+        }
+        write!(f, "])")?;
+        for (name, _) in self.parameters.iter() {
+            write!(f, ", Self::encode(&{})", name)?;
+        }
+        writeln!(f, ");")?;
+        if self.query.is_some() {
+            writeln!(f, "        let url = match serde_urlencoded::to_string(query) {{")?;
+            writeln!(f, "            Ok(query) => format!(\"{{}}?{{}}\", url, query),")?;
+            writeln!(f, "            Err(error) => return Box::new(futures::future::err(actix_web::error::ErrorBadRequest(error))),")?;
+            writeln!(f, "        }};")?;
+        }
+
+        write!(f, "        let response = self.client.request(actix_web::http::Method::{}, url)", self.method)?;
+        match self.body {
+            Some((send, _)) => writeln!(f, ".{}(body);", send)?,
+            None => writeln!(f, ".send();")?,
+        }
+        writeln!(f, "        let response = futures::Future::map_err(response, actix_web::error::ErrorInternalServerError);")?;
+
+        match self.response {
+            Some(ref response) => {
+                writeln!(f, "        Box::new(futures::Future::and_then(response, |mut response| {{")?;
+                writeln!(f, "            futures::Future::map_err(response.json::<{}>(), actix_web::error::ErrorInternalServerError)", response)?;
+                writeln!(f, "        }}))")?;
+            },
+            None => {
+                writeln!(f, "        Box::new(futures::Future::and_then(response, |mut response| {{")?;
+                writeln!(f, "            let status = response.status();")?;
+                writeln!(f, "            futures::Future::map_err(futures::Future::map(response.body(), move |body| (status, body.to_vec())), actix_web::error::ErrorInternalServerError)")?;
+                writeln!(f, "        }}))")?;
+            },
+        }
+
+        writeln!(f, "    }}\n")
+    }
+}
+
+///Client of scope.
+pub struct Client<'a> {
+    pub name: String,
+    ///Expression of type `&str` with scope's path.
+    pub path: String,
+    ///Names of arguments for `{..}` segments of scope's path.
+    pub parameters: Vec<String>,
+    pub methods: Vec<&'a Method>,
+    ///Name of trait with asynchronous methods, which client implements instead of inherent ones.
+    pub companion: Option<String>,
+}
+
+impl<'a> Client<'a> {
+    ///Creates client of scope with `path`, which is expression for `literal` path of scope.
+    pub fn new(name: String, literal: &str, path: String, methods: Vec<&'a Method>) -> Self {
+        let parameters = path_parameters(literal).iter().enumerate().map(|(idx, name)| format!("scope_{}", parameter_name(name, idx))).collect();
+
+        Self {
+            name,
+            path,
+            parameters,
+            methods,
+            companion: None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Client<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "///HTTP client of scope with method per route.")?;
+        writeln!(f, "pub struct {} {{", self.name)?;
+        writeln!(f, "    client: awc::Client,")?;
+        writeln!(f, "    url: String,")?;
+        writeln!(f, "    path: String,")?;
+        writeln!(f, "}}\n")?;
+
+        writeln!(f, "#[allow(dead_code)]")?;
+        writeln!(f, "impl {} {{", self.name)?;
+        writeln!(f, "    ///Creates client of scope, which is served at `base_url` (e.g. `http://localhost:8080`).")?;
+        writeln!(f, "    pub fn new(base_url: &str) -> Self {{")?;
+        writeln!(f, "        Self::with_client(awc::Client::default(), base_url)")?;
+        writeln!(f, "    }}\n")?;
+        writeln!(f, "    ///Creates client of scope, which uses configured `client`.")?;
+        writeln!(f, "    pub fn with_client(client: awc::Client, base_url: &str) -> Self {{")?;
+        writeln!(f, "        let url = base_url.trim_end_matches('/').to_owned();")?;
+        writeln!(f, "        let path = String::from({});", self.path)?;
+        writeln!(f, "        Self {{ client, url, path }}")?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Percent-encodes path parameter, leaving only unreserved characters as is.")?;
+        writeln!(f, "    fn encode(value: &dyn std::fmt::Display) -> String {{")?;
+        writeln!(f, "        let value = value.to_string();")?;
+        writeln!(f, "        let mut encoded = String::with_capacity(value.len());")?;
+        writeln!(f, "        for byte in value.bytes() {{")?;
+        writeln!(f, "            match byte {{")?;
+        writeln!(f, "                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),")?;
+        writeln!(f, "                byte => encoded.push_str(&format!(\"%{{:02X}}\", byte)),")?;
+        writeln!(f, "            }}")?;
+        writeln!(f, "        }}")?;
+        writeln!(f, "        encoded")?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Replaces `{{..}}` segments of scope's path with encoded `parameters`.")?;
+        writeln!(f, "    fn scope_path(&self, parameters: &[&dyn std::fmt::Display]) -> String {{")?;
+        writeln!(f, "        let mut path = String::with_capacity(self.path.len());")?;
+        writeln!(f, "        let mut rest = self.path.as_str();")?;
+        writeln!(f, "        let mut parameters = parameters.iter();")?;
+        writeln!(f, "        while let Some(start) = rest.find('{{') {{")?;
+        writeln!(f, "            match (rest[start..].find('}}'), parameters.next()) {{")?;
+        writeln!(f, "                (Some(end), Some(parameter)) => {{")?;
+        writeln!(f, "                    path.push_str(&rest[..start]);")?;
+        writeln!(f, "                    path.push_str(&Self::encode(*parameter));")?;
+        writeln!(f, "                    rest = &rest[start+end+1..];")?;
+        writeln!(f, "                }},")?;
+        writeln!(f, "                _ => break,")?;
+        writeln!(f, "            }}")?;
+        writeln!(f, "        }}")?;
+        writeln!(f, "        path.push_str(rest);")?;
+        writeln!(f, "        path")?;
+        writeln!(f, "    }}\n")?;
+
+        if self.companion.is_none() {
+            for method in self.methods.iter() {
+                method.write(f, "pub ", &self.parameters)?;
+            }
+        }
+        writeln!(f, "}}")?;
+
+        if let Some(ref companion) = self.companion {
+            writeln!(f, "\n///Asynchronous counterpart of scope's trait, which is implemented by `{}`.", self.name)?;
+            writeln!(f, "pub trait {} {{", companion)?;
+            for method in self.methods.iter() {
+                write!(f, "    ")?;
+                method.write_signature(f, &self.parameters)?;
+                writeln!(f, ";")?;
+            }
+            writeln!(f, "}}\n")?;
+
+            writeln!(f, "impl {} for {} {{", companion, self.name)?;
+            for method in self.methods.iter() {
+                method.write(f, "", &self.parameters)?;
+            }
+            writeln!(f, "}}")?;
+        }
+
+        Ok(())
+    }
+}
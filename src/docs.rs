@@ -0,0 +1,76 @@
+use std::fmt;
+
+///Page, which renders OpenAPI document, embedded instead of `__OPENAPI__`.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>API documentation</title>
+<style>
+body { font-family: sans-serif; margin: 2em auto; max-width: 60em; color: #222; }
+details { border: 1px solid #ccc; border-radius: 4px; margin: 0.5em 0; padding: 0.5em; }
+summary { cursor: pointer; }
+.method { display: inline-block; min-width: 5em; font-weight: bold; text-transform: uppercase; }
+.path { font-family: monospace; }
+.tags { color: #777; margin-left: 1em; }
+pre { background: #f5f5f5; padding: 0.5em; overflow: auto; }
+</style>
+</head>
+<body>
+<h1 id="title"></h1>
+<div id="operations"></div>
+<h2>OpenAPI</h2>
+<pre id="document"></pre>
+<script id="openapi" type="application/json">__OPENAPI__</script>
+<script>
+(function() {
+    var text = function(tag, value, cls) {
+        var element = document.createElement(tag);
+        element.textContent = value;
+        if (cls) {
+            element.className = cls;
+        }
+        return element;
+    };
+    var api = JSON.parse(document.getElementById("openapi").textContent);
+    document.getElementById("title").textContent = api.info.title + " " + api.info.version;
+    document.getElementById("document").textContent = JSON.stringify(api, null, 2);
+
+    var operations = document.getElementById("operations");
+    Object.keys(api.paths).forEach(function(path) {
+        Object.keys(api.paths[path]).forEach(function(method) {
+            var operation = api.paths[path][method];
+            var details = document.createElement("details");
+            var summary = document.createElement("summary");
+            summary.appendChild(text("span", method, "method"));
+            summary.appendChild(text("span", path, "path"));
+            if (operation.summary) {
+                summary.appendChild(text("span", " - " + operation.summary));
+            }
+            if (operation.tags.length) {
+                summary.appendChild(text("span", operation.tags.join(", "), "tags"));
+            }
+            details.appendChild(summary);
+            if (operation.description) {
+                details.appendChild(text("p", operation.description));
+            }
+            details.appendChild(text("pre", JSON.stringify(operation, null, 2)));
+            operations.appendChild(details);
+        });
+    });
+})();
+</script>
+</body>
+</html>
+"#;
+
+///Writes `.service(..)` calls, which serve OpenAPI document `docs` and page, which renders it, under `path`.
+///
+///Page is served at `path`, while document is at `path/openapi.json`.
+pub fn write_services(f: &mut fmt::Formatter, path: &str) -> fmt::Result {
+    write!(f, "\n        .service(actix_web::Resource::new({:?}).route(actix_web::Route::new().guard(actix_web::guard::Get())", format!("{}/openapi.json", path))?;
+    write!(f, ".to({{ let docs = docs.clone(); move || actix_web::HttpResponse::Ok().content_type(\"application/json\").body(docs.clone()) }})))")?;
+    write!(f, "\n        .service(actix_web::Resource::new({:?}).route(actix_web::Route::new().guard(actix_web::guard::Get())", path)?;
+    write!(f, ".to({{ let page = {:?}.replace(\"__OPENAPI__\", &docs.replace(\"</\", \"<\\\\/\")); ", PAGE)?;
+    write!(f, "move || actix_web::HttpResponse::Ok().content_type(\"text/html; charset=utf-8\").body(page.clone()) }})))")
+}
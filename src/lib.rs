@@ -18,6 +18,38 @@
 //! - `"path"` - Raw literal string with path for which to register handle. Mandatory.
 //! - `async` - Attribute to indicate that registered function is asynchronous.
 //! - `guard="function_name"` - Registers function as guard using `actix_web::guard::fn_guard`
+//! - `data="expression"` - Attaches result of expression as application data of the resource.
+//! - `hook="function_name"` - Calls function `Resource<P> -> Resource<P>` on resource before registration.
+//! - `head` - Registers `GET` handler for `HEAD` requests too. Response body is omitted by server.
+//! - `manifest` - Writes route manifest (see below).
+//!
+//! Generated service exposes route's metadata as associated constants:
+//! `NAME`, `PATH`, `METHODS` (empty for any method), `GUARDS` and `DOC` (handler's doc comment).
+//!
+//! Every generated service, including scopes, provides `routes()`, which returns list of
+//! `(path, methods, name)` with full paths of routes.
+//!
+//! With `runtime` feature enabled, same metadata is available through traits of companion crate
+//! `actix-web-cute-runtime`: route services implement `RouteInfo`, while scopes implement `ScopeInfo`.
+//! Therefore crate, which enables it, must depend on `actix-web-cute-runtime`.
+//!
+//! ## OpenAPI
+//!
+//! Every generated service provides `openapi()`, which returns OpenAPI 3 document as JSON
+//! (that is valid YAML as well). Document is assembled from:
+//!
+//! - Paths, methods and path parameters (i.e. `{..}` segments) of routes.
+//! - Extractors of handler: `Path`, `Query`, `Json` and `Form`. Primitives and collections
+//!   (e.g. `u32`, `Vec<String>`, `HashMap<String, T>`) are mapped to schemas, while other types,
+//!   which have no schema, are referred as `x-rust-type`. Path parameters are typed only if `Path`
+//!   extractor describes all of them (e.g. `Path<(u32, String)>` for `/{id}/{name}`).
+//! - Return type of handler: `Json<T>` is described by schema of `T`, strings as `text/plain`,
+//!   other types by their schema, if it is known, and by `x-rust-type` in any case.
+//! - Doc comment of handler: first line is summary, the rest is description.
+//! - `summary` and `tag` attributes of route, and `tag` attribute of scope.
+//!
+//! Scope with `docs="/path"` attribute serves its document at `/path/openapi.json`
+//! together with self-contained page, which renders it, at `/path`.
 //!
 //! ## Scope
 //!
@@ -29,6 +61,84 @@
 //!
 //! - `"path"` - Raw literal string with path for which to register handle. Mandatory.
 //! - `guard="function_name"` - Registers function as guard using `actix_web::guard::fn_guard`
+//! - `data="expression"` - Attaches result of expression as application data of the scope.
+//! - `scope="scope_name"` - Registers nested scope.
+//! - `path_env="VARIABLE"` - Environment variable to read path from on registration.
+//! - `lock="routes.lock"` - Checks route table against lock file (see below).
+//! - `options` - Responds to `OPTIONS` requests with list of allowed methods.
+//! - `auto_head` - Registers every `GET` handler for `HEAD` requests too.
+//! - `magic_names` - Detects `init` and `default_resource` members by name.
+//! - `strict` - Turns warnings about suspicious definitions (e.g. duplicate routes) into errors.
+//! - `manifest` - Writes route manifest (see below).
+//! - `client` - Generates HTTP client (see below).
+//! - `smoke_test` - Generates `#[cfg(test)]` smoke test of routes (see below).
+//!
+//! Handlers with the same path are registered as single resource, which responds
+//! with `405 Method Not Allowed` to methods without handler. Handlers with `guard` are registered
+//! as separate resources, so that requests rejected by guard are not answered by them.
+//!
+//! Routes of scope keep their services (e.g. `mod_inner_scope::test`), which can be registered on their own.
+//!
+//! ## Manifest
+//!
+//! Route or scope with `manifest` attribute writes its route table into `OUT_DIR/routes.<module>.<name>.json`
+//! during compilation, which requires build script. `<module>` is path of module file (e.g. `api.users`
+//! for `src/api/users.rs`), omitted within crate's root. Inline modules are not part of `<module>`,
+//! so names of items with manifest should be unique within file. Table lists full path, methods, name, guards
+//! and source location of every route, including routes of nested scopes declared inline
+//! (with their default path). It is intended for gateway configuration and similar tooling.
+//!
+//! Environment variable `CUTE_CODEGEN_MANIFEST_DIR` enables manifest for every route and scope,
+//! writing it into specified directory instead. Derived scopes don't write manifest,
+//! while scopes declared by `impl` blocks don't accept `manifest`.
+//!
+//! ## Client
+//!
+//! Scope with `client` attribute generates `Client` within its module, created by `client(base_url)`
+//! of scope, with method per route. Arguments of method are parameters of scope's path (`scope_<name>`),
+//! path parameters, typed by `Path` extractor, `query` and `body`, typed by `Query`, `Json` and `Form` extractors.
+//! Path parameters are percent-encoded. Method returns boxed future,
+//! which resolves to decoded value if handler returns `Json<T>`, or to status and body otherwise.
+//!
+//! Generated code requires `awc` and `futures` (and `serde_urlencoded` for queries) to be dependencies.
+//! Routes without method guard and nested scopes are not included, as nested scopes generate own client.
+//! Scopes declared by `impl` blocks don't support client.
+//!
+//! ## Smoke test
+//!
+//! Scope with `smoke_test` attribute provides `smoke_test()` in tests, which serves scope by
+//! `actix_http_test::TestServer` and sends request per method and path of its own routes, filling path parameters with `1`.
+//! It asserts that every request reaches route of the same name, rather than other route, default resource or service.
+//! Route is identified by marker guard, which routes of such scope register within tests only and which records
+//! token of request, passed by `x-smoke-test` header, via `actix-web-cute-runtime`.
+//! `smoke_test_with(prepare)` allows to modify requests (e.g. to pass guards).
+//!
+//! Routes of nested scopes and handlers are not requested, so nested scopes need `smoke_test` of their own.
+//! Scopes declared by `impl` blocks and traits don't support smoke test.
+//!
+//! Generated code requires `actix-http`, `actix-http-test` and `awc`, as well as `actix-web-cute-runtime`,
+//! to be dev-dependencies.
+//!
+//! ## TypeScript
+//!
+//! With `typescript` feature enabled and `CUTE_CODEGEN_TS_DIR` set, routes, scopes and traits
+//! write TypeScript module `routes.<module>.<name>.ts` (named same as manifest) into that directory,
+//! next to type definitions `<Name>.ts`. It contains `fetch` wrapper per route,
+//! which takes base URL, path parameters (including ones of scope's path), `query` and `body`,
+//! and resolves to decoded `Json<T>` response or to text otherwise. Routes of nested scopes, declared inline,
+//! are prefixed with name of scope.
+//!
+//! Types of extractors are converted into TypeScript, while other types are imported from modules,
+//! written by `#[derive(TypeScript)]`. Therefore exported types must have unique names within crate,
+//! and items, which would write the same module, fail compilation.
+//!
+//! ## Lock
+//!
+//! Scope with `lock="routes.lock"` compares its route table against file, relative to crate's root,
+//! which is meant to be committed. Every line of it is method, full path and path parameters of route.
+//! Routes that are removed or changed fail compilation, while new routes are reported as warnings.
+//! Missing lock fails compilation too. Lock is created or regenerated only when `CUTE_CODEGEN_UPDATE_LOCK` is set.
+//! Every scope needs its own lock file, so scopes sharing it fail compilation.
 //!
 //! ## Notes
 //!
@@ -52,6 +162,13 @@ extern crate proc_macro;
 
 mod route;
 mod scope;
+mod openapi;
+mod docs;
+mod manifest;
+mod lock;
+mod client;
+mod typescript;
+mod smoke;
 
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
@@ -65,6 +182,14 @@ use syn::parse_macro_input;
 /// - `"path"` - Raw literal string with path for which to register handler. Mandatory.
 /// - `async` - Attribute to indicate that registered function is asynchronous.
 /// - `guard="function_name"` - Registers function as guard using `actix_web::guard::fn_guard`
+/// - `data="expression"` - Attaches result of expression as application data of the resource.
+/// - `summary="text"` - Summary of OpenAPI operation. By default first line of doc comment.
+/// - `tag="name"` - Tag of OpenAPI operation. Can be specified multiple times.
+/// - `hook="function_name"` - Calls function `Resource<P> -> Resource<P>` on resource before registration.
+///   Within scope, handlers with the same path share resource, so their hooks are applied to it together
+///   (reported by scope's lint), unless handler has `guard` or `data`.
+/// - `head` - Registers `GET` handler for `HEAD` requests too. Allowed only for [get](attr.get.html).
+/// - `manifest` - Writes route manifest into `OUT_DIR/routes.<module>.<name>.json`.
 #[proc_macro_attribute]
 pub fn handler(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as syn::AttributeArgs);
@@ -124,8 +249,9 @@ pub fn delete(args: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// Syntax: `#[scope("path"[, attributes])]`
 ///
-/// Due to current limitation it cannot be applied to modules themself.
-/// Instead one should create const variable that contains module code.
+/// Can be applied to module or to const variable that contains module code.
+/// Module declared in separate file (`mod api;`) is supported only when compiler
+/// passes its content to the macro (i.e. with `proc_macro_hygiene` feature).
 ///
 /// ## Attributes:
 ///
@@ -133,13 +259,84 @@ pub fn delete(args: TokenStream, input: TokenStream) -> TokenStream {
 /// - `hook="function_name"` - Registers function to be run on scope before registering everything else.
 /// - `guard="function_name"` - Registers function as guard using `actix_web::guard::fn_guard`
 /// - `hanlder="function_name"` - Registers route hanlder as part of scope.
+/// - `scope="scope_name"` - Registers nested scope, which path is relative to this scope.
+/// - `path_env="VARIABLE"` - Environment variable to read path from on registration. Falls back to `"path"`.
+/// - `data="expression"` - Attaches result of expression as application data of the scope.
+/// - `tag="name"` - Tag of OpenAPI operations of scope, including nested ones.
+/// - `lock="routes.lock"` - Fails compilation if routes listed in lock file are removed or changed.
+///   Path is relative to crate's root. Create or regenerate lock by setting `CUTE_CODEGEN_UPDATE_LOCK`.
+/// - `docs="/path"` - Serves OpenAPI document at `/path/openapi.json` and page, which renders it, at `/path`.
+/// - `options` - Responds to `OPTIONS` requests with list of allowed methods.
+/// - `auto_head` - Registers every `GET` handler for `HEAD` requests too.
+/// - `magic_names` - Treats unmarked members `init` as `#[hook]` and `default_resource` as `#[default_resource]`.
+/// - `strict` - Turns lints into compilation errors.
+/// - `client` - Generates `Client` with method per route, created by `client(base_url)` of scope.
+/// - `smoke_test` - Generates `#[cfg(test)]` `smoke_test()`, which asserts that every route is reachable.
+/// - `manifest` - Writes route manifest of scope, including nested scopes, into `OUT_DIR/routes.<module>.<name>.json`.
+///
+/// ## Lints:
+///
+/// Scope reports suspicious definitions as warnings (i.e. use of deprecated item):
+///
+/// - Routes with the same method and path.
+/// - Routes with the same path, but different resource hooks, which are applied to shared resource.
+/// - Functions `Scope<P> -> Scope<P>` without `#[hook]`. Silenced by `#[allow(dead_code)]`.
+/// - Unmarked functions `init` and `default_resource` without `magic_names`, which are no longer registered
+///   by their name. Silenced by `#[allow(dead_code)]`.
+/// - Routes that are placed after catch-all pattern (e.g. `/{tail:.*}`) and therefore unreachable.
+///   Patterns are matched segment by segment, so `/{id}/{tail:.*}` shadows `/items/list`, but not `/items`.
+/// - Routes with multiple route attributes, and route attributes on items other than functions.
+///
+/// ```rust,compile_fail
+/// use actix_web_cute_codegen::{scope};
+///
+/// #[scope("/files", strict)]
+/// mod files {
+///     use actix_web_cute_codegen::{get};
+///     use actix_web::{HttpResponse, Responder};
+///
+///     #[get("/{id}/{tail:.*}")]
+///     pub fn file() -> impl Responder {
+///         HttpResponse::Ok()
+///     }
+///
+///     #[get("/images/logo.png")]
+///     pub fn logo() -> impl Responder {
+///         HttpResponse::Ok()
+///     }
+/// }
+/// ```
+///
+/// Members are recognized by attributes, which refer to macros of this crate either by name,
+/// by full path (e.g. `#[actix_web_cute_codegen::get("/")]`) or by alias imported within scope
+/// (e.g. `use actix_web_cute_codegen::get as http_get;`). Other attributes (e.g. `#[serde::get]`) are ignored.
+///
+/// Generated service provides `routes()` and `openapi()` with full paths of its routes, including routes
+/// of nested scopes and handlers. Routes of services not generated by this crate are omitted.
+///
+/// Handlers with the same path are registered as single resource.
+/// Unless one of them is registered via `handler`, resource responds to other methods
+/// with `405 Method Not Allowed` and `Allow` header. Handlers with `guard` or `data` are registered as
+/// separate resources and are not listed in `Allow` header.
+///
+/// Every route remains service (e.g. `mod_inner_scope::test`) as generated by route macro,
+/// while its function is renamed and registered by scope. Guards, data and hooks of routes
+/// are resolved within scope's module, same as by route macro.
 ///
 /// ## Special members:
 ///
-/// - `#[hook]` functions - are going to be run on scope before registering everything else
+/// - `#[hook]` functions - are going to be run on scope after guards and data, but before registering services.
+///   Accepts following options:
+///   - `order = N` - Hooks are run in ascending order, defaulting to `0`. Ties keep declaration order.
+///   - `after_services` - Runs hook after all services are registered.
+///   - `config` - Hook takes `&mut actix_web::dev::ServiceConfig<P>` and is called before scope is registered,
+///     or after it, if combined with `after_services`. Not available within `impl` blocks.
 /// - `#[guard]` functions - specifies function to be passed to `guard_fn`.
-/// - `init` - Scope initialization function. Used as `hook`
-/// - `default_resource` - function will be used as default method to the scope.
+/// - `#[default_resource]` function - will be used as default method to the scope.
+/// - `#[default_service]` function - returns `HttpServiceFactory` that is registered after every other service,
+///   including ones added by `after_services` hooks. It is not default resource of scope, so it handles
+///   only requests matched by its own path, which should be catch-all (e.g. `/{tail:.*}` for static files).
+/// - `#[scope]` modules and consts - are registered as nested scopes, inheriting path, guards and hooks.
 ///
 /// # Example
 ///
@@ -147,8 +344,8 @@ pub fn delete(args: TokenStream, input: TokenStream) -> TokenStream {
 /// use actix_web_cute_codegen::{scope};
 ///
 /// #[scope("/scope")]
-/// const mod_inner: () = {
-///     use actix_web_cute_codegen::{get, hook};
+/// mod mod_inner {
+///     use actix_web_cute_codegen::{get, hook, default_resource};
 ///     use actix_web::{HttpResponse, Responder};
 ///     use futures::{Future, future};
 ///
@@ -162,28 +359,81 @@ pub fn delete(args: TokenStream, input: TokenStream) -> TokenStream {
 ///         future::ok(HttpResponse::Ok().finish())
 ///     }
 ///
-///     ///Special function to initialize scope. Called as hook before routes registration.
+///     ///Called as hook before routes registration.
+///     #[hook]
 ///     pub fn init<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
 ///         scope
 ///     }
 ///
+///     #[default_resource]
 ///     pub fn default_resource<P: 'static>(res: actix_web::Resource<P>) -> actix_web::Resource<P> {
 ///         res.to(|| HttpResponse::InternalServerError())
 ///     }
 ///
-/// };
+/// }
+///
+/// let app = actix_web::App::new().service(mod_inner::scope);
 /// ```
 ///
 /// # Note
 ///
-/// Internally the macro generate struct with name of scope (e.g. `mod_inner`)
+/// When applied to module, the macro generates struct `scope` inside of module (e.g. `mod_inner::scope`).
+///
+/// When applied to const, the macro generates struct with name of scope (e.g. `mod_inner`)
 /// And create public module as `<name>_scope`
+///
+/// # Trait
+///
+/// When applied to trait, the macro declares API, which consists only of routes:
+///
+/// - `<Trait>Scope<T>(pub T)` - service, which serves any implementation `T` of trait.
+///   Methods taking `&self` are called on `T`, which is stored as scope's data.
+/// - `<Trait>Client` - HTTP client with method per route, generated with `client` attribute.
+/// - `<Trait>Async` - asynchronous counterpart of trait, implemented by `<Trait>Client`.
+///   Its methods return futures, which resolve to response or to error of request.
+///
+/// Trait scope accepts path, `path_env` and flags except `manifest` and `smoke_test`.
+///
+/// ```rust,ignore
+/// #[scope("/users", client)]
+/// pub trait UsersApi {
+///     #[get("/{id}")]
+///     fn get(&self, id: web::Path<u32>) -> web::Json<User>;
+/// }
+///
+/// let app = actix_web::App::new().service(UsersApiScope(Users::new()));
+/// let user = UsersApiAsync::get(&UsersApiClient::new("http://localhost:8080"), 1);
+/// ```
+///
+/// Implementation of trait is not scope itself, so it cannot be marked by `scope`:
+///
+/// ```rust,compile_fail
+/// use actix_web_cute_codegen::{get, scope};
+///
+/// #[scope("/static")]
+/// trait StaticApi {
+///     #[get("/hello")]
+///     fn hello() -> String;
+/// }
+///
+/// struct Static;
+///
+/// #[scope]
+/// impl StaticApi for Static {
+///     fn hello() -> String {
+///         "hello".to_owned()
+///     }
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn scope(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as syn::AttributeArgs);
-    match args.len() {
-        0 => scope::attr::ImplScope::new(input).generate(),
-        _ => scope::attr::Args::new(&args, input).generate(),
+    if syn::parse::<syn::ItemImpl>(input.clone()).is_ok() {
+        scope::attr::ImplScope::new(&args, input).generate()
+    } else if syn::parse::<syn::ItemTrait>(input.clone()).is_ok() {
+        scope::attr::TraitScope::new(&args, input).generate()
+    } else {
+        scope::attr::Args::new(&args, input).generate()
     }
 }
 
@@ -202,10 +452,73 @@ pub fn hook(_args: TokenStream, input: TokenStream) -> TokenStream {
     input
 }
 
+///Marks function as default resource of scope.
+#[proc_macro_attribute]
+pub fn default_resource(_args: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
+///Marks function as default service of scope.
+///
+///Function returns `HttpServiceFactory` to be registered after every other service.
+///Service should match any path (e.g. resource `/{tail:.*}`), as it receives only requests matched by it.
+#[proc_macro_attribute]
+pub fn default_service(_args: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
 ///Generates Scope
 ///
 ///Similar to [scope](attr.scope.html) macro
 ///
+///Implements `actix_web::dev::HttpServiceFactory` for struct, so that it can be registered as service.
+///Members of `#[scope] impl` block, if present, are added to the scope on registration.
+///
+///`#[scope]` on impl block accepts only `options` and `auto_head` attributes, while path is specified by derive.
+///Impl block can be generic and its type can be specified by path (e.g. `impl<R: Repo> api::ApiScope<R>`).
+///
+///Handlers and hooks may take `&self`, in which case struct must implement `Clone`.
+///Handlers are called on instance stored as `web::Data<Self>`, while hooks are called on clone
+///of instance, made before scope is created.
+///
+///## Container attribute:
+///
+///`#[scope(path="path"[, attributes])]`, which must be placed after `#[derive(Scope)]`
+///
+///- `path="path"` - Raw literal string with path for which to register scope. Mandatory.
+///- `path_env="VARIABLE"` - Environment variable to read path from on registration. Falls back to `path`.
+///- `guard="function_name"` - Registers function as guard using `actix_web::guard::fn_guard`
+///- `wrap="expression"` - Wraps scope with middleware, created by expression.
+///- `default="function_name"` - Function to be used as default resource of the scope.
+///- `tag="name"` - Tag of OpenAPI operations of scope.
+///- `docs="/path"` - Serves OpenAPI document at `/path/openapi.json` and page, which renders it, at `/path`.
+///
+///## Field attributes:
+///
+///- `#[service]` - Registers field as service of the scope.
+///- `#[scope]` - Registers field as nested scope.
+///- `#[guard]` - Registers field as guard of the scope.
+///- `#[data]` - Moves field into application data of the scope.
+///- `#[hook]` - Calls field with scope before registering everything else.
+///- `#[default_resource]` - Field will be used as default resource of the scope.
+///
+///Struct provides `routes()` and `openapi()` with full paths of routes, which are declared by its
+///`#[scope] impl` and types of service fields (i.e. only statically known routes).
+///
+///`#[service]` and `#[scope]` fields of type `Option<S>` are registered only when `Some`,
+///while fields of type `Vec<S>` are registered element by element.
+///
+///Tuple structs are supported as well.
+///
+///## Builder:
+///
+///Derive generates `<Name>Builder`, which allows to override path and to add guards at runtime:
+///
+///`MyScope::new().builder().path("/internal/my_scope").guard(actix_web::guard::Get()).build()`
+///
+///Builder's `routes()` and `openapi()` use configured path, same as `routes_with_path(path)` and
+///`openapi_with_path(path)` of struct. Document served by `docs` uses actual path of scope.
+///
 ///## Example
 ///
 ///```rust
@@ -218,7 +531,7 @@ pub fn hook(_args: TokenStream, input: TokenStream) -> TokenStream {
 ///}
 ///
 ///#[derive(Scope)]
-///#[path="/my_scope"]
+///#[scope(path="/my_scope")]
 ///pub struct MyScope {
 ///    #[service]
 ///    test: test,
@@ -235,7 +548,7 @@ pub fn hook(_args: TokenStream, input: TokenStream) -> TokenStream {
 ///        }
 ///    }
 ///
-///    //Special member to act as hook
+///    #[hook]
 ///    pub fn init<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
 ///        scope
 ///    }
@@ -247,6 +560,8 @@ pub fn hook(_args: TokenStream, input: TokenStream) -> TokenStream {
 ///    }
 ///
 ///
+///    //Not a hook, so it is not called
+///    #[allow(dead_code)]
 ///    pub fn init_scope_unused<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
 ///        scope
 ///    }
@@ -256,14 +571,38 @@ pub fn hook(_args: TokenStream, input: TokenStream) -> TokenStream {
 ///        future::ok(actix_web::HttpResponse::Ok().finish())
 ///    }
 ///
+///    #[default_resource]
 ///    pub fn default_resource<P: 'static>(res: actix_web::Resource<P>) -> actix_web::Resource<P> {
 ///        res.to(|| actix_web::HttpResponse::InternalServerError())
 ///    }
 ///}
 ///```
-#[proc_macro_derive(Scope, attributes(path, service, guard))]
+#[proc_macro_derive(Scope, attributes(scope, service, guard, data, hook, default_resource))]
 pub fn parser_derive(input: TokenStream) -> TokenStream {
     let ast: syn::DeriveInput = syn::parse(input).unwrap();
     let gen = scope::derive::Args::new(ast);
     gen.generate()
 }
+
+///Exports type as TypeScript definition `<Name>.ts`, when `typescript` feature is enabled.
+///
+///Structs with named fields become interfaces, newtypes become aliases and enums with unit variants
+///become unions of strings, respecting serde's `rename_all` of type, and `rename` and `skip` of fields and variants.
+///Serde's attributes, which change shape of type (e.g. `tag` or `flatten`), are not supported.
+///
+///Unsupported types fail compilation even when feature is disabled:
+///
+///```rust,compile_fail
+///use actix_web_cute_codegen::TypeScript;
+///
+///#[derive(TypeScript)]
+///enum Shape {
+///    Circle(f64),
+///}
+///```
+#[proc_macro_derive(TypeScript, attributes(serde))]
+pub fn typescript_derive(input: TokenStream) -> TokenStream {
+    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+    typescript::write_definition(&ast);
+    TokenStream::new()
+}
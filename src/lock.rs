@@ -0,0 +1,71 @@
+use std::{env, fs, io};
+use std::path::PathBuf;
+
+use crate::manifest::{self, Entry, Location};
+use crate::route::path_parameters;
+
+///Environment variable, which makes scopes rewrite their lock files.
+const UPDATE_LOCK: &str = "CUTE_CODEGEN_UPDATE_LOCK";
+
+///Returns path of lock `file`, relative to crate's root.
+pub fn path(file: &str) -> PathBuf {
+    match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir).join(file),
+        None => PathBuf::from(file),
+    }
+}
+
+///Returns lines of lock, one per method of route: `METHOD /path param1,param2`.
+///
+///Routes without method guard are listed with `*` method.
+fn lines(entries: &[Entry]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for entry in entries {
+        let parameters = path_parameters(&entry.path);
+        let methods = match entry.methods.is_empty() {
+            true => vec!["*"],
+            false => entry.methods.clone(),
+        };
+
+        for method in methods {
+            let line = match parameters.is_empty() {
+                true => format!("{} {}", method, entry.path),
+                false => format!("{} {} {}", method, entry.path, parameters.join(",")),
+            };
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+///Compares route table of scope `name` against lock `file`, returning routes missing from it.
+///
+///Panics if lock is missing or contains routes, that are removed or changed.
+///Lock is created or rewritten only when `CUTE_CODEGEN_UPDATE_LOCK` is set.
+///Lock belongs to single scope, so scopes sharing it fail compilation.
+pub fn check(file: &str, name: &str, entries: &[Entry]) -> Vec<String> {
+    let path = path(file);
+    manifest::claim(&path, &Location::call_site(), name);
+    let actual = lines(entries);
+
+    if env::var_os(UPDATE_LOCK).is_some() {
+        let text = format!("# Route table of scope. Regenerate with {}=1\n{}\n", UPDATE_LOCK, actual.join("\n"));
+        if let Err(error) = fs::write(&path, text) {
+            panic!("Unable to write route lock '{}': {}", path.display(), error);
+        }
+        return Vec::new();
+    }
+
+    let expected = match fs::read_to_string(&path) {
+        Ok(text) => text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')).map(String::from).collect::<Vec<_>>(),
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => panic!("Route lock '{}' is missing. Create it with {}=1", path.display(), UPDATE_LOCK),
+        Err(error) => panic!("Unable to read route lock '{}': {}", path.display(), error),
+    };
+
+    let removed = expected.iter().filter(|line| !actual.contains(line)).map(String::as_str).collect::<Vec<_>>();
+    if !removed.is_empty() {
+        panic!("Routes of '{}' are removed or changed:\n{}\nRestore them or regenerate lock with {}=1", path.display(), removed.join("\n"), UPDATE_LOCK);
+    }
+
+    actual.into_iter().filter(|line| !expected.contains(line)).collect()
+}
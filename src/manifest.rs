@@ -0,0 +1,142 @@
+use std::{env, fs};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::openapi::json_string;
+
+///Environment variable with directory to write manifests into.
+///
+///When set, manifests are written for every route and scope.
+const MANIFEST_DIR: &str = "CUTE_CODEGEN_MANIFEST_DIR";
+
+///Location of handler in source code.
+#[derive(Clone)]
+pub struct Location {
+    file: String,
+    line: usize,
+}
+
+impl Location {
+    pub fn from_span(span: proc_macro::Span) -> Self {
+        Self {
+            file: span.file(),
+            line: span.line(),
+        }
+    }
+
+    ///Returns location of item, which macro is applied to.
+    pub fn call_site() -> Self {
+        Self::from_span(proc_macro::Span::call_site())
+    }
+
+    ///Returns module path of source file (e.g. `api.users` of `src/api/users/mod.rs`), joined by `separator`.
+    ///
+    ///Items of crate's root have empty module path. Modules declared inline are not part of it.
+    pub fn module_path(&self, separator: &str) -> String {
+        let path = Path::new(&self.file).with_extension("");
+        let mut segments = path.components().map(|segment| segment.as_os_str().to_string_lossy().into_owned())
+                                            .skip_while(|segment| segment != "src")
+                                            .skip(1)
+                                            .collect::<Vec<_>>();
+
+        match segments.last().map(String::as_str) {
+            Some("lib") | Some("main") | Some("mod") => {
+                segments.pop();
+            },
+            _ => (),
+        }
+
+        segments.join(separator)
+    }
+
+    ///Returns stem of file with routes of item `name` (e.g. `routes.api.users.test`), which is declared at this location.
+    pub fn routes_file(&self, name: &str) -> String {
+        match self.module_path(".") {
+            ref module if module.is_empty() => format!("routes.{}", name),
+            module => format!("routes.{}.{}", module, name),
+        }
+    }
+}
+
+///Files written during compilation and items, which wrote them.
+static WRITTEN: Mutex<Vec<(PathBuf, String)>> = Mutex::new(Vec::new());
+
+///Remembers that `path` is written by item `name` at `location`, panicking if other item has written it already.
+///
+///Item may be expanded multiple times (e.g. by IDE, while it is edited), so it is identified by module path
+///and name, rather than by line, and its own file is not collision.
+pub fn claim(path: &Path, location: &Location, name: &str) {
+    let item = match location.module_path("::") {
+        ref module if module.is_empty() => format!("crate::{}", name),
+        module => format!("crate::{}::{}", module, name),
+    };
+    let mut written = WRITTEN.lock().unwrap_or_else(|error| error.into_inner());
+
+    match written.iter().find(|(written, _)| written == path) {
+        Some((_, other)) if *other != item => panic!("'{}' is written by both '{}' and '{}', while every item needs its own file", path.display(), other, item),
+        Some(_) => (),
+        None => written.push((path.to_owned(), item)),
+    }
+}
+
+///Entry of route manifest.
+pub struct Entry {
+    pub path: String,
+    pub methods: Vec<&'static str>,
+    pub name: String,
+    pub guards: Vec<String>,
+    pub location: Option<Location>,
+}
+
+impl Entry {
+    fn to_json(&self) -> String {
+        let methods = self.methods.iter().map(|method| json_string(method)).collect::<Vec<_>>().join(",");
+        let guards = self.guards.iter().map(|guard| json_string(guard)).collect::<Vec<_>>().join(",");
+        let location = match self.location {
+            Some(ref location) => format!(",\"file\":{},\"line\":{}", json_string(&location.file), location.line),
+            None => String::new(),
+        };
+
+        format!("{{\"path\":{},\"methods\":[{}],\"name\":{},\"guards\":[{}]{}}}", json_string(&self.path), methods, json_string(&self.name), guards, location)
+    }
+}
+
+///Returns directory to write manifests into, if it is enabled.
+///
+///Manifest is enabled by `manifest` attribute, which writes into `OUT_DIR`,
+///or for every item by `CUTE_CODEGEN_MANIFEST_DIR`, which also overrides directory.
+fn directory(is_enabled: bool) -> Option<PathBuf> {
+    match env::var_os(MANIFEST_DIR) {
+        Some(dir) => Some(dir.into()),
+        None if is_enabled => match env::var_os("OUT_DIR") {
+            Some(dir) => Some(dir.into()),
+            None => panic!("Route manifest requires OUT_DIR (i.e. build script) or {} to be set", MANIFEST_DIR),
+        },
+        None => None,
+    }
+}
+
+///Writes manifest `routes.<module>.<name>.json` with `entries`, if it is enabled.
+///
+///Module path of item (see `Location::module_path`) is omitted for items of crate's root.
+pub fn write(name: &str, entries: &[Entry], is_enabled: bool) {
+    let dir = match directory(is_enabled) {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    let entries = entries.iter().map(Entry::to_json).collect::<Vec<_>>();
+    let manifest = format!("[\n{}\n]\n", entries.join(",\n"));
+
+    if let Err(error) = fs::create_dir_all(&dir) {
+        panic!("Unable to create directory '{}' for route manifest: {}", dir.display(), error);
+    }
+
+    let location = Location::call_site();
+    let path = dir.join(format!("{}.json", location.routes_file(name)));
+    claim(&path, &location, name);
+
+    if let Err(error) = fs::write(&path, manifest) {
+        panic!("Unable to write route manifest '{}': {}", path.display(), error);
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt;
+
+use crate::route::{doc_comment, RouteAttrs};
+
+///Type returned by `openapi_operations()` of generated services.
+///
+///Contains full path, lowercase method, tags, comma separated parameters and fields of operation object,
+///which are completed with path parameters once document is built, and schemas of path parameters.
+pub const OPERATIONS_TYPE: &str = "Vec<(String, &'static str, Vec<&'static str>, &'static str, &'static str, &'static [&'static str])>";
+
+///Code of `openapi()`, which builds document out of `operations`.
+///
+///Path parameters are taken from `{..}` segments of full path, as prefix of scope may contain them too.
+///Their schemas are known only if `Path` extractor describes every parameter of full path.
+///Paths and tags are known only at runtime, so they are escaped as `json_string` does.
+const DOCUMENT: &str = r#"
+        let escape = |text: &str| {
+            let mut result = String::with_capacity(text.len() + 2);
+            result.push('"');
+            for ch in text.chars() {
+                match ch {
+                    '"' => result.push_str("\\\""),
+                    '\\' => result.push_str("\\\\"),
+                    '\n' => result.push_str("\\n"),
+                    '\r' => result.push_str("\\r"),
+                    '\t' => result.push_str("\\t"),
+                    ch if (ch as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", ch as u32)),
+                    ch => result.push(ch),
+                }
+            }
+            result.push('"');
+            result
+        };
+
+        let mut paths: Vec<(String, Vec<String>)> = Vec::new();
+        for (path, method, tags, parameters, fields, schemas) in operations {
+            let mut normalized = String::new();
+            let mut names = Vec::new();
+            let mut rest = path.as_str();
+            while let Some(start) = rest.find('{') {
+                let end = match rest[start..].find('}') {
+                    Some(end) => start + end,
+                    None => break,
+                };
+                let name = rest[start+1..end].split(':').next().unwrap_or("").trim();
+                normalized.push_str(&rest[..start]);
+                normalized.push_str(&format!("{{{}}}", name));
+                names.push(name);
+                rest = &rest[end+1..];
+            }
+            normalized.push_str(rest);
+
+            let mut path_parameters = names.iter().enumerate().map(|(idx, name)| {
+                let schema = match schemas.len() == names.len() {
+                    true => schemas[idx],
+                    false => "{}",
+                };
+                format!("{{\"name\":{},\"in\":\"path\",\"required\":true,\"schema\":{}}}", escape(name), schema)
+            }).collect::<Vec<_>>();
+
+            if !parameters.is_empty() {
+                path_parameters.push(parameters.to_owned());
+            }
+            let tags = tags.iter().map(|tag| escape(tag)).collect::<Vec<_>>().join(",");
+            let operation = format!("\"{}\":{{\"tags\":[{}],\"parameters\":[{}]{}}}", method, tags, path_parameters.join(","), fields);
+
+            match paths.iter_mut().find(|(path, _)| *path == normalized) {
+                Some((_, operations)) => operations.push(operation),
+                None => paths.push((normalized, vec![operation])),
+            }
+        }
+
+        let paths = paths.iter().map(|(path, operations)| format!("{}:{{{}}}", escape(path), operations.join(","))).collect::<Vec<_>>().join(",");
+        format!("{{\"openapi\":\"3.0.0\",\"info\":{{\"title\":{},\"version\":{}}},\"paths\":{{{}}}}}", escape(env!("CARGO_PKG_NAME")), escape(env!("CARGO_PKG_VERSION")), paths)
+"#;
+
+///Writes `openapi()`, which builds document from `Self::openapi_operations()`.
+pub fn write_document(f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "    ///Returns OpenAPI 3 document in JSON format, which is valid YAML too.")?;
+    writeln!(f, "    pub fn openapi() -> String {{")?;
+    writeln!(f, "        let operations = Self::openapi_operations();")?;
+    f.write_str(DOCUMENT)?;
+    writeln!(f, "    }}")
+}
+
+///Writes `openapi_with_path()`, which builds document from `Self::openapi_operations_with_path()`.
+pub fn write_document_with_path(f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "    ///Returns OpenAPI 3 document of scope, which is served at `path`.")?;
+    writeln!(f, "    pub fn openapi_with_path(path: &str) -> String {{")?;
+    writeln!(f, "        let operations = Self::openapi_operations_with_path(path);")?;
+    f.write_str(DOCUMENT)?;
+    writeln!(f, "    }}")
+}
+
+///Escapes text as JSON string.
+pub fn json_string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+///Returns type arguments of path segment (e.g. `T` of `Vec<T>`).
+pub fn type_args(segment: &syn::PathSegment) -> Vec<&syn::Type> {
+    match segment.arguments {
+        syn::PathArguments::AngleBracketed(ref args) => args.args.iter().filter_map(|arg| match arg {
+            syn::GenericArgument::Type(typ) => Some(typ),
+            _ => None,
+        }).collect(),
+        _ => Vec::new(),
+    }
+}
+
+///Returns JSON schema of type, mapping primitives and collections same as TypeScript modules.
+///
+///Other types (e.g. user's structs) are described only by `x-rust-type`.
+pub fn schema(typ: &syn::Type) -> String {
+    match typ {
+        syn::Type::Reference(typ) => schema(&typ.elem),
+        syn::Type::Paren(typ) => schema(&typ.elem),
+        syn::Type::Group(typ) => schema(&typ.elem),
+        syn::Type::Slice(typ) => format!("{{\"type\":\"array\",\"items\":{}}}", schema(&typ.elem)),
+        syn::Type::Array(typ) => format!("{{\"type\":\"array\",\"items\":{}}}", schema(&typ.elem)),
+        syn::Type::Path(path) => {
+            let segment = match path.path.segments.iter().last() {
+                Some(segment) => segment,
+                None => return format!("{{\"x-rust-type\":{}}}", json_string(&quote::quote!(#typ).to_string())),
+            };
+
+            match (segment.ident.to_string().as_str(), type_args(segment).as_slice()) {
+                ("u8", _) | ("u16", _) | ("u32", _) | ("u64", _) | ("usize", _) |
+                ("i8", _) | ("i16", _) | ("i32", _) | ("i64", _) | ("isize", _) => "{\"type\":\"integer\"}".to_owned(),
+                ("f32", _) | ("f64", _) => "{\"type\":\"number\"}".to_owned(),
+                ("String", _) | ("str", _) | ("char", _) => "{\"type\":\"string\"}".to_owned(),
+                ("bool", _) => "{\"type\":\"boolean\"}".to_owned(),
+                ("Vec", [typ]) | ("VecDeque", [typ]) | ("HashSet", [typ]) | ("BTreeSet", [typ]) => format!("{{\"type\":\"array\",\"items\":{}}}", schema(typ)),
+                ("Option", [typ]) => {
+                    let inner = schema(typ);
+                    format!("{},\"nullable\":true}}", &inner[..inner.len()-1])
+                },
+                ("HashMap", [_, typ]) | ("BTreeMap", [_, typ]) => format!("{{\"type\":\"object\",\"additionalProperties\":{}}}", schema(typ)),
+                ("Box", [typ]) | ("Rc", [typ]) | ("Arc", [typ]) | ("Cow", [typ]) => schema(typ),
+                _ => format!("{{\"x-rust-type\":{}}}", json_string(&quote::quote!(#typ).to_string())),
+            }
+        },
+        _ => format!("{{\"x-rust-type\":{}}}", json_string(&quote::quote!(#typ).to_string())),
+    }
+}
+
+///Returns content type and schema of handler's response, if it is known.
+///
+///`Json<T>` is described by schema of `T`, while strings are plain text.
+///Other types are described only if their schema is known (e.g. not user's struct).
+fn response_schema(typ: &syn::Type) -> Option<(&'static str, String)> {
+    if let Some((ref name, ref inner)) = extractor(typ) {
+        if name == "Json" {
+            let inner = syn::parse_str::<syn::Type>(inner).expect("Parse type of extractor");
+            return Some(("application/json", schema(&inner)));
+        }
+    }
+
+    match schema(typ) {
+        ref schema if schema.starts_with("{\"x-rust-type\"") => None,
+        ref schema if schema == "{\"type\":\"string\"}" => Some(("text/plain", schema.clone())),
+        schema => Some(("application/json", schema)),
+    }
+}
+
+///Returns name of extractor and its type argument (e.g. `Json` and `User` of `web::Json<User>`).
+pub fn extractor(typ: &syn::Type) -> Option<(String, String)> {
+    let segment = match typ {
+        syn::Type::Path(typ) => typ.path.segments.iter().last()?,
+        _ => return None,
+    };
+
+    let inner = match segment.arguments {
+        syn::PathArguments::AngleBracketed(ref args) => match args.args.iter().next() {
+            Some(syn::GenericArgument::Type(ref typ)) => quote::quote!(#typ).to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some((segment.ident.to_string(), inner))
+}
+
+///OpenAPI operation of route.
+pub struct Operation {
+    tags: Vec<String>,
+    parameters: String,
+    fields: String,
+    path_schemas: Vec<String>,
+}
+
+impl Operation {
+    pub fn new(fun: &syn::ItemFn, attrs: &RouteAttrs) -> Self {
+        let doc = doc_comment(&fun.attrs);
+        let mut lines = doc.splitn(2, '\n');
+        let doc_summary = lines.next().filter(|line| !line.is_empty());
+        let description = lines.next().map(str::trim).filter(|text| !text.is_empty());
+
+        let mut parameters = Vec::new();
+        let mut path_schemas = Vec::new();
+        let mut fields = format!(",\"operationId\":{}", json_string(&fun.ident.to_string()));
+
+        if let Some(summary) = attrs.summary.as_ref().map(|summary| &summary[..]).or(doc_summary) {
+            fields.push_str(&format!(",\"summary\":{}", json_string(summary)));
+        }
+        if let Some(description) = description {
+            fields.push_str(&format!(",\"description\":{}", json_string(description)));
+        }
+
+        for arg in fun.decl.inputs.iter() {
+            let typ = match arg {
+                syn::FnArg::Captured(arg) => &arg.ty,
+                _ => continue,
+            };
+
+            let parse = |inner: &str| syn::parse_str::<syn::Type>(inner).expect("Parse type of extractor");
+            match extractor(typ) {
+                Some((ref name, ref inner)) if name == "Path" => {
+                    fields.push_str(&format!(",\"x-rust-path\":{}", json_string(inner)));
+                    path_schemas = match parse(inner) {
+                        syn::Type::Tuple(ref typ) => typ.elems.iter().map(schema).collect(),
+                        ref typ => vec![schema(typ)],
+                    };
+                },
+                Some((ref name, ref inner)) if name == "Query" => {
+                    parameters.push(format!("{{\"name\":\"query\",\"in\":\"query\",\"style\":\"form\",\"explode\":true,\"schema\":{}}}", schema(&parse(inner))));
+                },
+                Some((ref name, ref inner)) if name == "Json" || name == "Form" => {
+                    let content = match name.as_str() {
+                        "Json" => "application/json",
+                        _ => "application/x-www-form-urlencoded",
+                    };
+                    fields.push_str(&format!(",\"requestBody\":{{\"required\":true,\"content\":{{\"{}\":{{\"schema\":{}}}}}}}", content, schema(&parse(inner))));
+                },
+                _ => continue,
+            }
+        }
+
+        let response = match fun.decl.output {
+            syn::ReturnType::Type(_, ref typ) => {
+                let mut response = format!(",\"x-rust-type\":{}", json_string(&quote::quote!(#typ).to_string()));
+                if let Some((content, schema)) = response_schema(typ) {
+                    response.push_str(&format!(",\"content\":{{\"{}\":{{\"schema\":{}}}}}", content, schema));
+                }
+                response
+            },
+            syn::ReturnType::Default => String::new(),
+        };
+        fields.push_str(&format!(",\"responses\":{{\"default\":{{\"description\":\"Response of handler\"{}}}}}", response));
+
+        Self {
+            tags: attrs.tags.clone(),
+            parameters: parameters.join(","),
+            fields,
+            path_schemas,
+        }
+    }
+
+    ///Writes tuple of operation for `method` with `path` expression of type `String`.
+    pub fn write(&self, f: &mut fmt::Formatter, path: &str, method: &str) -> fmt::Result {
+        write!(f, "({}, \"{}\", vec!{:?}, {:?}, {:?}, &{:?})", path, method.to_lowercase(), self.tags, self.parameters, self.fields, self.path_schemas)
+    }
+}
@@ -5,6 +5,10 @@ use std::fmt;
 use proc_macro::TokenStream;
 use quote::{quote};
 
+use crate::openapi::{self, Operation, OPERATIONS_TYPE};
+use crate::manifest::{self, Location};
+use crate::typescript;
+
 pub enum ResourceType {
     Async,
     Sync,
@@ -19,7 +23,7 @@ impl fmt::Display for ResourceType {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub enum GuardType {
     None,
     Get,
@@ -28,6 +32,19 @@ pub enum GuardType {
     Delete,
 }
 
+impl GuardType {
+    ///Returns HTTP method name, if guard restricts method.
+    pub fn method(&self) -> Option<&'static str> {
+        match *self {
+            GuardType::None => None,
+            GuardType::Get => Some("GET"),
+            GuardType::Post => Some("POST"),
+            GuardType::Put => Some("PUT"),
+            GuardType::Delete => Some("DELETE"),
+        }
+    }
+}
+
 impl fmt::Display for GuardType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -40,6 +57,11 @@ impl fmt::Display for GuardType {
     }
 }
 
+///Type returned by `routes()` of generated services: full path, methods and name of handler.
+///
+///Empty methods mean that handler accepts any method.
+pub const ROUTES_TYPE: &str = "Vec<(String, &'static [&'static str], &'static str)>";
+
 pub struct Args {
     name: syn::Ident,
     path: String,
@@ -47,6 +69,56 @@ pub struct Args {
     resource_type: ResourceType,
     pub guard: GuardType,
     pub extra_guards: Vec<String>,
+    pub data: Vec<String>,
+    pub hooks: Vec<String>,
+    pub head: bool,
+    ///Function, which handles requests, when it is declared outside of service (i.e. by scope).
+    ///Otherwise function is declared within service's registration.
+    handler: Option<syn::Ident>,
+    operation: Operation,
+    manifest: bool,
+}
+
+///Attributes of route macro
+pub struct RouteAttrs {
+    pub path: Option<String>,
+    pub resource_type: ResourceType,
+    pub extra_guards: Vec<String>,
+    pub data: Vec<String>,
+    ///Functions `Resource<P> -> Resource<P>` to be applied to resource before registration.
+    pub hooks: Vec<String>,
+    pub head: bool,
+    ///Summary of OpenAPI operation, overriding first line of doc comment.
+    pub summary: Option<String>,
+    ///Tags of OpenAPI operation.
+    pub tags: Vec<String>,
+    ///Whether to write route manifest.
+    pub manifest: bool,
+}
+
+///Returns names of path parameters (i.e. `{..}` segments).
+pub fn path_parameters(path: &str) -> Vec<&str> {
+    let mut parameters = Vec::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        parameters.push(rest[start+1..end].split(':').next().unwrap_or("").trim());
+        rest = &rest[end+1..];
+    }
+    parameters
+}
+
+///Returns documentation of item, joining lines of doc comments.
+pub fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    let lines = attrs.iter().filter(|attr| attr.path.is_ident("doc")).filter_map(|attr| match attr.parse_meta() {
+        Ok(syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(ref text), .. })) => Some(text.value().trim().to_owned()),
+        _ => None,
+    });
+
+    lines.collect::<Vec<_>>().join("\n")
 }
 
 pub fn guess_resource_type(typ: &syn::Type) -> ResourceType {
@@ -76,10 +148,16 @@ pub fn guess_resource_type(typ: &syn::Type) -> ResourceType {
 
 }
 
-pub fn parse_meta_attrs(fun: &syn::ItemFn, args: &[syn::NestedMeta]) -> (Option<String>, ResourceType, Vec<String>) {
+pub fn parse_meta_attrs(fun: &syn::ItemFn, args: &[syn::NestedMeta], guard: &GuardType) -> RouteAttrs {
     let mut resource_type = None;
+    let mut head = false;
+    let mut manifest = false;
 
     let mut extra_guards = Vec::new();
+    let mut data = Vec::new();
+    let mut hooks = Vec::new();
+    let mut summary = None;
+    let mut tags = Vec::new();
     let mut path = None;
 
     for arg in args {
@@ -93,14 +171,35 @@ pub fn parse_meta_attrs(fun: &syn::ItemFn, args: &[syn::NestedMeta]) -> (Option<
             },
             syn::NestedMeta::Meta(syn::Meta::Word(ident)) => match ident.to_string().to_lowercase().as_str() {
                 "async" => resource_type = Some(ResourceType::Async),
-                unknown => panic!("Unknown attribute {}. Allowed: async", unknown),
+                "head" => match guard {
+                    &GuardType::Get => head = true,
+                    _ => panic!("Attribute head is allowed only for GET routes"),
+                },
+                "manifest" => manifest = true,
+                unknown => panic!("Unknown attribute {}. Allowed: async, head, manifest", unknown),
             },
             syn::NestedMeta::Meta(syn::Meta::NameValue(ident)) => match ident.ident.to_string().to_lowercase().as_str() {
                 "guard" => match ident.lit {
                     syn::Lit::Str(ref text) => extra_guards.push(text.value()),
                     _ => panic!("Attribute guard expects literal string!"),
                 },
-                attr => panic!("Unknown attribute key is specified: {}. Allowed: guard", attr)
+                "data" => match ident.lit {
+                    syn::Lit::Str(ref text) => data.push(text.value()),
+                    _ => panic!("Attribute data expects literal string!"),
+                },
+                "hook" => match ident.lit {
+                    syn::Lit::Str(ref text) => hooks.push(text.value()),
+                    _ => panic!("Attribute hook expects literal string!"),
+                },
+                "summary" => match ident.lit {
+                    syn::Lit::Str(ref text) => summary = Some(text.value()),
+                    _ => panic!("Attribute summary expects literal string!"),
+                },
+                "tag" => match ident.lit {
+                    syn::Lit::Str(ref text) => tags.push(text.value()),
+                    _ => panic!("Attribute tag expects literal string!"),
+                },
+                attr => panic!("Unknown attribute key is specified: {}. Allowed: guard, data, hook, summary, tag", attr)
             },
             attr => panic!("Unknown attribute {:?}", attr)
         }
@@ -117,7 +216,17 @@ pub fn parse_meta_attrs(fun: &syn::ItemFn, args: &[syn::NestedMeta]) -> (Option<
         }
     };
 
-    (path, resource_type, extra_guards)
+    RouteAttrs {
+        path,
+        resource_type,
+        extra_guards,
+        data,
+        hooks,
+        head,
+        summary,
+        tags,
+        manifest,
+    }
 }
 
 impl Args {
@@ -126,10 +235,36 @@ impl Args {
             panic!("invalid server definition, expected: #[{}(\"some path\")]", guard);
         }
 
+        let location = input.clone().into_iter().next().map(|token| Location::from_span(token.span()));
         let ast: syn::ItemFn = syn::parse(input).expect("Parse input as function");
+        let result = Self::from_fn(ast, args, guard, None);
+
+        manifest::write(&result.name.to_string(), &[manifest::Entry {
+            path: result.path.clone(),
+            methods: result.methods(),
+            name: result.name.to_string(),
+            guards: result.extra_guards.clone(),
+            location,
+        }], result.manifest);
+
+        if let Some(function) = typescript::Function::new(&result.ast, &result.path, &result.guard) {
+            typescript::write_module(&result.name.to_string(), &[(String::new(), String::new(), function)]);
+        }
+
+        result
+    }
+
+    ///Creates service of scope's member `ast`, which is declared by scope as function `handler`.
+    pub fn member(ast: syn::ItemFn, args: &[syn::NestedMeta], guard: GuardType, handler: syn::Ident) -> Self {
+        Self::from_fn(ast, args, guard, Some(handler))
+    }
+
+    fn from_fn(ast: syn::ItemFn, args: &[syn::NestedMeta], guard: GuardType, handler: Option<syn::Ident>) -> Self {
         let name = ast.ident.clone();
 
-        let (path, resource_type, extra_guards) = parse_meta_attrs(&ast, args);
+        let attrs = parse_meta_attrs(&ast, args, &guard);
+        let operation = Operation::new(&ast, &attrs);
+        let RouteAttrs { path, resource_type, extra_guards, data, hooks, head, manifest, .. } = attrs;
 
         let path = path.expect("Route's path is not specified!");
 
@@ -140,6 +275,12 @@ impl Args {
             resource_type,
             guard,
             extra_guards,
+            data,
+            hooks,
+            head,
+            handler,
+            operation,
+            manifest,
         }
     }
 
@@ -153,23 +294,91 @@ impl Args {
     }
 }
 
+impl Args {
+    ///Returns methods of route, including `HEAD` when enabled.
+    fn methods(&self) -> Vec<&'static str> {
+        let mut methods = Vec::new();
+        methods.extend(self.guard.method());
+        if self.head {
+            methods.push("HEAD");
+        }
+        methods
+    }
+
+    ///Writes route's metadata as associated constants of service.
+    fn write_info(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let methods = self.methods();
+
+        writeln!(f, "#[allow(dead_code)]")?;
+        writeln!(f, "impl {} {{", self.name)?;
+        writeln!(f, "    pub const NAME: &'static str = {:?};", self.name.to_string())?;
+        writeln!(f, "    pub const PATH: &'static str = {:?};", self.path)?;
+        writeln!(f, "    pub const METHODS: &'static [&'static str] = &{:?};", methods)?;
+        writeln!(f, "    pub const GUARDS: &'static [&'static str] = &{:?};", self.extra_guards)?;
+        writeln!(f, "    pub const DOC: &'static str = {:?};\n", doc_comment(&self.ast.attrs))?;
+        writeln!(f, "    pub fn routes() -> {} {{", ROUTES_TYPE)?;
+        writeln!(f, "        vec![(String::from(Self::PATH), Self::METHODS, Self::NAME)]")?;
+        writeln!(f, "    }}\n")?;
+        writeln!(f, "    pub fn openapi_operations() -> {} {{", OPERATIONS_TYPE)?;
+        write!(f, "        vec![")?;
+        for method in methods.iter() {
+            self.operation.write(f, "String::from(Self::PATH)", method)?;
+            write!(f, ", ")?;
+        }
+        writeln!(f, "]")?;
+        writeln!(f, "    }}\n")?;
+        openapi::write_document(f)?;
+        writeln!(f, "}}\n")?;
+
+        if !cfg!(feature = "runtime") {
+            return Ok(());
+        }
+
+        writeln!(f, "impl actix_web_cute_runtime::RouteInfo for {} {{", self.name)?;
+        for name in ["NAME", "PATH", "METHODS", "GUARDS", "DOC"].iter() {
+            let typ = match *name {
+                "METHODS" | "GUARDS" => "&'static [&'static str]",
+                _ => "&'static str",
+            };
+            writeln!(f, "    const {1}: {2} = {0}::{1};", self.name, name, typ)?;
+        }
+        writeln!(f, "}}\n")
+    }
+}
+
 impl fmt::Display for Args {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let ast = &self.ast;
 
         writeln!(f, "#[allow(non_camel_case_types)]")?;
+        //Service of scope's member is not used by scope itself
+        if self.handler.is_some() {
+            writeln!(f, "#[allow(dead_code)]")?;
+        }
         writeln!(f, "pub struct {};\n", self.name)?;
+        self.write_info(f)?;
         writeln!(f, "impl<P: 'static> actix_web::dev::HttpServiceFactory<P> for {} {{", self.name)?;
         writeln!(f, "    fn register(self, config: &mut actix_web::dev::ServiceConfig<P>) {{")?;
-        writeln!(f, "        {}\n", quote!(#ast))?;
-        write!(f, "        let resource = actix_web::Resource::new(\"{}\")", self.path)?;
-        if self.guard != GuardType::None {
+        if self.handler.is_none() {
+            writeln!(f, "        {}\n", quote!(#ast))?;
+        }
+        write!(f, "        let resource = actix_web::Resource::new({:?})", self.path)?;
+        if self.head {
+            write!(f, ".guard(actix_web::guard::Any(actix_web::guard::{}()).or(actix_web::guard::Head()))", self.guard)?;
+        } else if self.guard != GuardType::None {
             write!(f, ".guard(actix_web::guard::{}())", self.guard)?;
         }
         for guard in self.extra_guards.iter() {
-            write!(f, ".guard(actix_web::guard::guard_fn({}))", guard)?;
+            write!(f, ".guard(actix_web::guard::fn_guard({}))", guard)?;
+        }
+        for data in self.data.iter() {
+            write!(f, ".data({})", data)?;
+        }
+        writeln!(f, ".{}({});", self.resource_type, self.handler.as_ref().unwrap_or(&self.name))?;
+        for hook in self.hooks.iter() {
+            writeln!(f, "        let resource = {}(resource);", hook)?;
         }
-        writeln!(f, ".{}({});\n", self.resource_type, self.name)?;
+        writeln!(f)?;
         writeln!(f, "        actix_web::dev::HttpServiceFactory::register(resource, config)")?;
         writeln!(f, "    }}\n}}")
     }
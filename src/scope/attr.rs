@@ -1,82 +1,866 @@
 use quote::{quote};
 use proc_macro::TokenStream;
 
-use crate::route::{GuardType};
+use crate::route::{self, GuardType, RouteAttrs};
+use crate::route::ROUTES_TYPE;
+use crate::openapi::{self, Operation, OPERATIONS_TYPE};
+use crate::manifest::{self, Location};
+use crate::lock;
+use crate::client;
+use crate::typescript;
+use crate::smoke;
+use super::{path_expr, service_param, write_routes_fallback, write_nested_routes, write_scope_info, write_nested_operations, write_operations_tags};
 use std::{mem, fmt};
-use std::collections::HashSet;
+
+const CRATE_NAME: &str = "actix_web_cute_codegen";
+
+///Attributes that declare route.
+const ROUTE_ATTRS: [&str; 5] = ["get", "post", "put", "delete", "handler"];
+
+///Attributes that are consumed by scope from its members.
+const USED_ATTRS: [&str; 9] = ["get", "post", "put", "delete", "handler", "hook", "guard", "default_resource", "default_service"];
+
+///Resolves attributes of scope's members to macros of this crate.
+///
+///Attribute is recognized when it is either:
+///
+///- Name of macro, or its alias imported by `use actix_web_cute_codegen::{get as http_get}` within scope.
+///- Full path to macro (e.g. `actix_web_cute_codegen::get`).
+#[derive(Default)]
+struct AttrResolver {
+    ///Pairs of local name and macro's name.
+    aliases: Vec<(String, String)>,
+}
+
+impl AttrResolver {
+    fn from_items(items: &[syn::Item]) -> Self {
+        let mut resolver = Self::default();
+
+        for item in items {
+            if let syn::Item::Use(ref item) = item {
+                if let syn::UseTree::Path(ref path) = item.tree {
+                    if path.ident == CRATE_NAME {
+                        resolver.add_use(&path.tree);
+                    }
+                }
+            }
+        }
+
+        resolver
+    }
+
+    fn add_use(&mut self, tree: &syn::UseTree) {
+        match tree {
+            syn::UseTree::Name(name) => self.aliases.push((name.ident.to_string(), name.ident.to_string())),
+            syn::UseTree::Rename(name) => self.aliases.push((name.rename.to_string(), name.ident.to_string())),
+            syn::UseTree::Group(group) => for tree in group.items.iter() {
+                self.add_use(tree);
+            },
+            _ => (),
+        }
+    }
+
+    ///Returns name of macro, which attribute refers to.
+    fn resolve(&self, attr: &syn::Attribute) -> Option<String> {
+        let segments = attr.path.segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>();
+
+        let name = match segments.as_slice() {
+            [name] => match self.aliases.iter().find(|(alias, _)| alias == name) {
+                Some((_, name)) => name.clone(),
+                None => name.clone(),
+            },
+            [krate, name] if krate == CRATE_NAME => name.clone(),
+            _ => return None,
+        };
+
+        match USED_ATTRS.contains(&name.as_str()) || name == "scope" {
+            true => Some(name),
+            false => None,
+        }
+    }
+
+    fn is(&self, attr: &syn::Attribute, name: &str) -> bool {
+        self.resolve(attr).map(|attr| attr == name).unwrap_or(false)
+    }
+
+    fn is_route(&self, attr: &syn::Attribute) -> bool {
+        self.resolve(attr).map(|attr| ROUTE_ATTRS.contains(&attr.as_str())).unwrap_or(false)
+    }
+
+    ///Returns whether attribute is consumed by scope.
+    fn is_used(&self, attr: &syn::Attribute) -> bool {
+        self.resolve(attr).map(|attr| USED_ATTRS.contains(&attr.as_str())).unwrap_or(false)
+    }
+
+    fn is_scope(&self, attr: &syn::Attribute) -> bool {
+        self.is(attr, "scope")
+    }
+}
+
+///Returns whether function is method with `&self` receiver.
+fn is_by_ref(fun: &syn::ItemFn) -> bool {
+    match fun.decl.inputs.iter().next() {
+        Some(syn::FnArg::SelfRef(arg)) => match arg.mutability {
+            Some(_) => panic!("Scope's member '{}' cannot take &mut self", fun.ident),
+            None => true,
+        },
+        Some(syn::FnArg::SelfValue(_)) => panic!("Scope's member '{}' cannot take self by value", fun.ident),
+        _ => false,
+    }
+}
+
+///Returns whether type is path, which last segment is `name` (e.g. `actix_web::Scope<P>`).
+fn is_type_named(typ: &syn::Type, name: &str) -> bool {
+    match typ {
+        syn::Type::Path(typ) => typ.path.segments.iter().last().map(|segment| segment.ident == name).unwrap_or(false),
+        _ => false,
+    }
+}
+
+///Returns whether function looks like `Scope<P> -> Scope<P>` hook.
+fn is_hook_shaped(fun: &syn::ItemFn) -> bool {
+    let args = fun.decl.inputs.iter().filter(|arg| match arg {
+        syn::FnArg::SelfRef(_) | syn::FnArg::SelfValue(_) => false,
+        syn::FnArg::Captured(_) | syn::FnArg::Inferred(_) | syn::FnArg::Ignored(_) => true,
+    }).collect::<Vec<_>>();
+
+    let input = match args.as_slice() {
+        [syn::FnArg::Captured(arg)] => is_type_named(&arg.ty, "Scope"),
+        _ => false,
+    };
+    let output = match fun.decl.output {
+        syn::ReturnType::Type(_, ref typ) => is_type_named(typ, "Scope"),
+        syn::ReturnType::Default => false,
+    };
+
+    input && output
+}
+
+///Returns whether item has `#[allow(dead_code)]`, which silences lint of unused member.
+fn is_dead_code_allowed(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident("allow") && attr.tts.to_string().contains("dead_code"))
+}
+
+///Returns whether last segment of path is catch-all pattern (e.g. `/files/{tail:.*}`).
+fn is_catch_all(path: &str) -> bool {
+    match path.rsplit('/').next() {
+        Some(segment) => segment.ends_with(":.*}") && segment.matches('{').count() == 1,
+        None => false,
+    }
+}
+
+///Returns whether `path` is certainly matched by catch-all `pattern`.
+///
+///Segments are compared one by one, up to catch-all segment, which static prefix has to match too.
+///Dynamic segments of pattern match any segment, unless they have custom pattern (e.g. `{id:\d+}`),
+///in which case they are assumed to not match.
+fn is_catch_all_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+    let (tail, segments) = match pattern.split_last() {
+        Some(split) if path.len() >= pattern.len() => split,
+        _ => return false,
+    };
+
+    let is_matched = segments.iter().zip(path.iter()).all(|(segment, actual)| {
+        let is_dynamic = segment.starts_with('{') && segment.ends_with('}') && segment.matches('{').count() == 1 && !segment.contains(':');
+        match is_dynamic {
+            true => !actual.is_empty(),
+            false => segment == actual,
+        }
+    });
+    let prefix = &tail[..tail.find('{').unwrap_or(0)];
+
+    is_matched && path[segments.len()].starts_with(prefix)
+}
+
+///Compile-time diagnostics of scope.
+///
+///Emitted as use of deprecated item, so that they show up as compiler warnings,
+///or turned into errors in `strict` mode.
+#[derive(Default)]
+struct Lints {
+    messages: Vec<String>,
+}
+
+impl Lints {
+    fn push(&mut self, message: String) {
+        self.messages.push(message);
+    }
+
+    ///Checks route attributes on item, that cannot be consumed by scope.
+    fn check_unconsumed(&mut self, resolver: &AttrResolver, kind: &str, name: &syn::Ident, attrs: &[syn::Attribute]) {
+        if attrs.iter().any(|attr| resolver.is_used(attr)) {
+            self.push(format!("Attributes of {} '{}' are not consumed by scope. Only functions can be routes, hooks or guards", kind, name));
+        }
+    }
+
+    fn finish(&self, flags: &Flags) {
+        if flags.strict && !self.messages.is_empty() {
+            panic!("Scope has following problems:\n{}", self.messages.join("\n"));
+        }
+    }
+}
+
+impl fmt::Display for Lints {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for message in self.messages.iter() {
+            writeln!(f, "        {{")?;
+            writeln!(f, "            #[deprecated(note = {:?})]", message)?;
+            writeln!(f, "            #[allow(non_camel_case_types)]")?;
+            writeln!(f, "            struct scope_lint;")?;
+            writeln!(f, "            let _ = scope_lint;")?;
+            writeln!(f, "        }}")?;
+        }
+
+        Ok(())
+    }
+}
+
+///Flags of scope, specified as words in attributes.
+#[derive(Default)]
+struct Flags {
+    options: bool,
+    auto_head: bool,
+    ///Enables detection of `init` and `default_resource` members by their name.
+    magic_names: bool,
+    ///Turns lints into errors.
+    strict: bool,
+    ///Writes route manifest.
+    manifest: bool,
+    ///Generates HTTP client.
+    client: bool,
+    ///Generates `#[cfg(test)]` smoke test.
+    smoke_test: bool,
+}
+
+impl Flags {
+    fn parse(&mut self, ident: &syn::Ident) {
+        match ident.to_string().to_lowercase().as_str() {
+            "options" => self.options = true,
+            "auto_head" => self.auto_head = true,
+            "magic_names" => self.magic_names = true,
+            "strict" => self.strict = true,
+            "manifest" => self.manifest = true,
+            "client" => self.client = true,
+            "smoke_test" => self.smoke_test = true,
+            unknown => panic!("Unknown attribute {}. Allowed: options, auto_head, magic_names, strict, manifest, client, smoke_test", unknown),
+        }
+    }
+}
 
 #[derive(Default)]
 struct Items {
     guards: Vec<String>,
-    hooks: Vec<String>,
+    hooks: Vec<ScopeHook>,
     handlers: Vec<String>,
+    scopes: Vec<String>,
+    data: Vec<String>,
+    tags: Vec<String>,
+}
+
+struct ScopeRoute {
+    name: syn::Ident,
+    path: String,
+    guard: GuardType,
+    ///Arguments of route attribute.
+    args: Vec<syn::NestedMeta>,
+    ///Function, which handles requests.
+    handler: syn::Ident,
+    attrs: RouteAttrs,
+    ///Types of arguments for `&self` method, which is called with `web::Data<Self>`
+    by_ref: Option<Vec<syn::Type>>,
+    operation: Operation,
+    location: Option<Location>,
+    client: Option<client::Method>,
+    typescript: Option<typescript::Function>,
+}
+
+struct ScopeHook {
+    name: String,
+    by_ref: bool,
+    ///Hooks are run in ascending order, preserving order of declaration for equal values.
+    order: i64,
+    ///Whether hook is run after services are registered.
+    after_services: bool,
+    ///Whether hook takes `&mut ServiceConfig` instead of scope.
+    config: bool,
+}
+
+impl ScopeHook {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            by_ref: false,
+            order: 0,
+            after_services: false,
+            config: false,
+        }
+    }
+
+    fn from_fn(fun: &syn::ItemFn, resolver: &AttrResolver) -> Self {
+        let mut hook = Self::new(fun.ident.to_string());
+        hook.by_ref = is_by_ref(fun);
+
+        for attr in fun.attrs.iter().filter(|attr| resolver.is(attr, "hook")) {
+            let list = match attr.parse_meta() {
+                Ok(syn::Meta::Word(_)) => continue,
+                Ok(syn::Meta::List(list)) => list,
+                _ => panic!("Hook '{}' has invalid attribute, expected: #[hook(order = N, after_services, config)]", hook.name),
+            };
+
+            for meta in list.nested.iter() {
+                match meta {
+                    syn::NestedMeta::Meta(syn::Meta::Word(ident)) => match ident.to_string().as_str() {
+                        "after_services" => hook.after_services = true,
+                        "config" => hook.config = true,
+                        unknown => panic!("Unknown attribute {} of hook '{}'. Allowed: order, after_services, config", unknown, hook.name),
+                    },
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(ref meta)) if meta.ident == "order" => match meta.lit {
+                        syn::Lit::Int(ref order) => hook.order = order.value() as i64,
+                        _ => panic!("Attribute order of hook '{}' expects integer!", hook.name),
+                    },
+                    meta => panic!("Unknown attribute {} of hook '{}'. Allowed: order, after_services, config", quote!(#meta), hook.name),
+                }
+            }
+        }
+
+        if hook.config && hook.by_ref {
+            panic!("Hook '{}' with config cannot take &self", hook.name);
+        }
+
+        hook
+    }
+}
+
+///Sorts hooks by their order, returning them together with call path.
+fn sorted_hooks<'a>(hooks: impl Iterator<Item=(String, &'a ScopeHook)>) -> Vec<(String, &'a ScopeHook)> {
+    let mut hooks = hooks.collect::<Vec<_>>();
+    hooks.sort_by_key(|(_, hook)| hook.order);
+    hooks
+}
+
+impl ScopeRoute {
+    fn from_fn(fun: &syn::ItemFn, resolver: &AttrResolver) -> Self {
+        let mut guard = GuardType::None;
+        let mut args = Vec::new();
+        let mut location = None;
+
+        for attr in fun.attrs.iter() {
+            let is_found = match resolver.resolve(attr).as_ref().map(|name| &name[..]) {
+                Some("get") => {
+                    guard = GuardType::Get;
+                    true
+                },
+                Some("put") => {
+                    guard = GuardType::Put;
+                    true
+                },
+                Some("post") => {
+                    guard = GuardType::Post;
+                    true
+                },
+                Some("delete") => {
+                    guard = GuardType::Delete;
+                    true
+                },
+                Some("handler") => true,
+                _ => false,
+            };
+
+            if is_found {
+                let tokens: proc_macro::TokenStream = attr.tts.clone().into();
+                let tokens = match tokens.into_iter().next().expect("To have single token tree in route's args") {
+                    proc_macro::TokenTree::Group(group) => {
+                        location = Some(Location::from_span(group.span()));
+                        group.stream()
+                    },
+                    tokens => panic!("Expected route's args as TokenTree's Group, but got {:?}", tokens),
+                };
+                //That's hidden API, dangerous?
+                let mut tokens = syn::parse_macro_input::parse::<syn::AttributeArgs>(tokens).expect("To parse route's arguments");
+                mem::swap(&mut tokens, &mut args);
+                break;
+            }
+        }
+
+        let attrs = crate::route::parse_meta_attrs(fun, &args, &guard);
+        let operation = Operation::new(fun, &attrs);
+        let path = attrs.path.clone().expect("Route's handle misses path");
+        let client = client::Method::new(fun, &path, &guard);
+        let typescript = typescript::Function::new(fun, &path, &guard);
+
+        let by_ref = match is_by_ref(fun) {
+            true => Some(fun.decl.inputs.iter().skip(1).map(|arg| match arg {
+                syn::FnArg::Captured(arg) => arg.ty.clone(),
+                _ => panic!("Route handler '{}' has unsupported argument", fun.ident),
+            }).collect()),
+            false => None,
+        };
+
+        Self {
+            name: fun.ident.clone(),
+            path,
+            guard,
+            args,
+            handler: fun.ident.clone(),
+            attrs,
+            by_ref,
+            operation,
+            location,
+            client,
+            typescript,
+        }
+    }
+
+    ///Returns whether `GET` route is registered for `HEAD` too.
+    fn has_head(&self, flags: &Flags) -> bool {
+        self.guard == GuardType::Get && (self.attrs.head || flags.auto_head)
+    }
+
+    ///Returns methods of route, including `HEAD` when enabled.
+    fn methods(&self, flags: &Flags) -> Vec<&'static str> {
+        let mut methods = Vec::new();
+        if let Some(method) = self.guard.method() {
+            methods.push(method);
+        }
+        if self.has_head(flags) {
+            methods.push("HEAD");
+        }
+        methods
+    }
+
+    ///Writes `.to(..)` with route's handler.
+    fn write_handler(&self, f: &mut fmt::Formatter, handler_prefix: &str) -> fmt::Result {
+        match self.by_ref {
+            Some(ref args) => {
+                write!(f, ".{}(|this: actix_web::web::Data<Self>", self.attrs.resource_type)?;
+                for (idx, arg) in args.iter().enumerate() {
+                    write!(f, ", arg{}: {}", idx, quote!(#arg))?;
+                }
+                //Called by path, as method call could resolve to method of `Data` itself
+                write!(f, "| {}::{}(&*this", handler_prefix, self.name)?;
+                for idx in 0..args.len() {
+                    write!(f, ", arg{}", idx)?;
+                }
+                write!(f, "))")
+            },
+            None => write!(f, ".{}({}::{})", self.attrs.resource_type, handler_prefix, self.handler),
+        }
+    }
+
+    ///Writes route with optional method guard (e.g. `Get`), which is marked for smoke test, if it is enabled.
+    fn write_route(&self, f: &mut fmt::Formatter, flags: &Flags, method_guard: Option<&str>, handler_prefix: &str) -> fmt::Result {
+        write!(f, "\n            .route(")?;
+        let route = match method_guard {
+            Some(method_guard) => format!("actix_web::Route::new().guard(actix_web::guard::{}())", method_guard),
+            None => "actix_web::Route::new()".to_owned(),
+        };
+        match flags.smoke_test {
+            true => smoke::write_marked_route(f, &route, &self.name.to_string())?,
+            false => write!(f, "{}", route)?,
+        }
+
+        self.write_handler(f, handler_prefix)?;
+        write!(f, ")")
+    }
+
+    ///Writes own resource of route with guards, which are formatted using `route_guard`, or with data.
+    ///
+    ///Guards are checked by resource, so that requests rejected by them are not matched by route's path,
+    ///while data is not shared with other routes of the same path.
+    fn write_own_resource(&self, f: &mut fmt::Formatter, flags: &Flags, handler_prefix: &str, route_guard: &dyn Fn(&str) -> String) -> fmt::Result {
+        write!(f, "\n        .service(")?;
+        for hook in self.attrs.hooks.iter().rev() {
+            write!(f, "{}(", hook)?;
+        }
+        write!(f, "actix_web::Resource::new({:?})", self.path)?;
+
+        match self.guard {
+            GuardType::None => (),
+            guard if self.has_head(flags) => write!(f, ".guard(actix_web::guard::Any(actix_web::guard::{}()).or(actix_web::guard::Head()))", guard)?,
+            guard => write!(f, ".guard(actix_web::guard::{}())", guard)?,
+        }
+        for guard in self.attrs.extra_guards.iter() {
+            write!(f, ".guard({})", route_guard(guard))?;
+        }
+        for data in self.attrs.data.iter() {
+            write!(f, ".data({})", data)?;
+        }
+
+        self.write_route(f, flags, None, handler_prefix)?;
+
+        for _ in self.attrs.hooks.iter() {
+            write!(f, ")")?;
+        }
+        write!(f, ")")
+    }
+}
+
+///Scope of route, which is visited by `walk_routes`.
+struct RouteScope<'a> {
+    ///Full path of scope.
+    path: &'a str,
+    ///Prefix of names, which is empty for routes of scope itself, and `<nested>_` for nested scopes.
+    name_prefix: &'a str,
+    guards: &'a [String],
+    flags: &'a Flags,
+}
+
+///Visits routes of scope's `items`.
+///
+///Nested scopes, declared inline, are visited too, using their default path.
+fn walk_routes(scope: &RouteScope, items: &[syn::Item], resolver: &AttrResolver, visit: &mut dyn FnMut(&RouteScope, &ScopeRoute)) {
+    let scope_items = ScopeItems::from_items(items, scope.flags, resolver);
+    let mut guards = scope.guards.to_vec();
+    guards.extend(scope_items.guards.iter().cloned());
+
+    let route_scope = RouteScope {
+        guards: &guards,
+        ..*scope
+    };
+    for route in scope_items.routes.iter() {
+        visit(&route_scope, route);
+    }
+
+    for item in items {
+        let (name, attrs, module) = match item {
+            syn::Item::Mod(module) => (&module.ident, &module.attrs, module.clone()),
+            syn::Item::Const(item) => match item.expr.as_ref() {
+                syn::Expr::Block(expr) => (&item.ident, &item.attrs, syn::parse2(quote!(mod scope #expr)).expect("Parse scope's code block as module")),
+                _ => continue,
+            },
+            _ => continue,
+        };
+        let (attr, content) = match (attrs.iter().find(|attr| resolver.is_scope(attr)), module.content) {
+            (Some(attr), Some((_, content))) => (attr, content),
+            _ => continue,
+        };
+
+        let mut path = String::new();
+        let mut nested_flags = Flags::default();
+        let mut nested_guards = guards.clone();
+        if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
+            for meta in list.nested.iter() {
+                match meta {
+                    syn::NestedMeta::Literal(syn::Lit::Str(ref text)) => path = text.value(),
+                    syn::NestedMeta::Meta(syn::Meta::Word(ident)) => nested_flags.parse(ident),
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(ref meta)) if meta.ident == "guard" => match meta.lit {
+                        syn::Lit::Str(ref text) => nested_guards.push(text.value()),
+                        _ => continue,
+                    },
+                    _ => continue,
+                }
+            }
+        }
+
+        let nested = RouteScope {
+            path: &format!("{}{}", scope.path, path),
+            name_prefix: &format!("{}{}_", scope.name_prefix, name),
+            guards: &nested_guards,
+            flags: &nested_flags,
+        };
+        walk_routes(&nested, &content, &AttrResolver::from_items(&content), visit);
+    }
+}
+
+enum ScopeDefault {
+    ///Function `Resource<P> -> Resource<P>`, passed to `default_resource`.
+    Resource(String),
+    ///Function returning `HttpServiceFactory`, registered after every other service,
+    ///including ones of `after_services` hooks. It is expected to match any path.
+    Service(String),
+}
+
+impl ScopeDefault {
+    fn write(&self, f: &mut fmt::Formatter, prefix: &str) -> fmt::Result {
+        match self {
+            ScopeDefault::Resource(name) => write!(f, ".default_resource({}::{})", prefix, name),
+            ScopeDefault::Service(name) => write!(f, ".service({}::{}())", prefix, name),
+        }
+    }
 }
 
 struct ScopeItems {
-    handlers: Vec<String>,
+    routes: Vec<ScopeRoute>,
+    scopes: Vec<String>,
     guards: Vec<String>,
-    hooks: Vec<String>,
-    default: Option<String>,
-    raw_routes: Vec<syn::ItemFn>
+    hooks: Vec<ScopeHook>,
+    default: Option<ScopeDefault>,
+    lints: Lints,
 }
 
 impl ScopeItems {
-    pub fn from_items(items: &[syn::Item]) -> Self {
-        let mut handlers = Vec::new();
+    pub fn from_items(items: &[syn::Item], flags: &Flags, resolver: &AttrResolver) -> Self {
+        let mut routes = Vec::new();
+        let mut scopes = Vec::new();
         let mut guards = Vec::new();
         let mut hooks = Vec::new();
         let mut default = None;
-        let mut raw_routes = Vec::new();
+        let mut lints = Lints::default();
+
+        let mut set_default = |value: ScopeDefault| {
+            if default.is_some() {
+                panic!("Second default resource in scope! You cannot have more than one of default_resource and default_service");
+            }
+            default = Some(value);
+        };
 
         for item in items {
             match item {
                 syn::Item::Fn(ref fun) => {
-                    if fun.ident == "default_resource" {
-                        if default.is_some() {
-                            panic!("Second 'default_resource' in scope! You cannot have more than one default resource");
-                        }
-
-                        default = Some(format!("{}", fun.ident));
+                    if flags.magic_names && fun.ident == "default_resource" && !fun.attrs.iter().any(|attr| resolver.is_used(attr)) {
+                        set_default(ScopeDefault::Resource(fun.ident.to_string()));
                         continue;
-                    } else if fun.ident == "init" {
-                        hooks.push(fun.ident.to_string());
+                    } else if flags.magic_names && fun.ident == "init" && !fun.attrs.iter().any(|attr| resolver.is_used(attr)) {
+                        hooks.push(ScopeHook::from_fn(fun, resolver));
                         continue;
                     }
 
+                    let route_attrs = fun.attrs.iter().filter(|attr| resolver.is_route(attr)).count();
+                    if route_attrs > 1 {
+                        lints.push(format!("Route '{}' has {} route attributes, but only first one is used", fun.ident, route_attrs));
+                    }
+                    if !fun.attrs.iter().any(|attr| resolver.is_used(attr)) && !is_dead_code_allowed(&fun.attrs) {
+                        let marker = match fun.ident.to_string().as_str() {
+                            "init" => Some("hook"),
+                            "default_resource" => Some("default_resource"),
+                            _ => None,
+                        };
+                        if let Some(marker) = marker {
+                            lints.push(format!("Function '{}' is not registered by its name without magic_names. Mark it with #[{}] or add #[allow(dead_code)] if it is intended", fun.ident, marker));
+                        } else if is_hook_shaped(fun) {
+                            lints.push(format!("Function '{}' looks like hook, but is not marked with #[hook]. Add #[allow(dead_code)] if it is intended", fun.ident));
+                        }
+                    }
+
                     for attr in fun.attrs.iter() {
-                        for bound in attr.path.segments.iter() {
-                            if bound.ident == "get" || bound.ident == "put" || bound.ident == "handler" || bound.ident == "delete" || bound.ident == "post" {
-                                handlers.push(format!("{}", fun.ident));
-                                raw_routes.push(fun.clone());
-                                break;
-                            } else if bound.ident == "guard" {
-                                guards.push(format!("{}", fun.ident));
-                                break;
-                            } else if bound.ident == "hook" {
-                                hooks.push(format!("{}", fun.ident));
-                                break;
-                            }
+                        match resolver.resolve(attr).as_ref().map(|name| &name[..]) {
+                            Some("get") | Some("put") | Some("post") | Some("delete") | Some("handler") => routes.push(ScopeRoute::from_fn(fun, resolver)),
+                            Some("guard") => guards.push(format!("{}", fun.ident)),
+                            Some("hook") => hooks.push(ScopeHook::from_fn(fun, resolver)),
+                            Some("default_resource") => set_default(ScopeDefault::Resource(fun.ident.to_string())),
+                            Some("default_service") => set_default(ScopeDefault::Service(fun.ident.to_string())),
+                            _ => continue,
                         }
+                        break;
                     }
                 },
+                syn::Item::Mod(ref module) if module.attrs.iter().any(|attr| resolver.is_scope(attr)) => {
+                    scopes.push(format!("{}::scope", module.ident));
+                },
+                syn::Item::Const(ref module) if module.attrs.iter().any(|attr| resolver.is_scope(attr)) => {
+                    scopes.push(module.ident.to_string());
+                },
+                syn::Item::Const(ref item) => lints.check_unconsumed(resolver, "const", &item.ident, &item.attrs),
+                syn::Item::Static(ref item) => lints.check_unconsumed(resolver, "static", &item.ident, &item.attrs),
+                syn::Item::Struct(ref item) => lints.check_unconsumed(resolver, "struct", &item.ident, &item.attrs),
                 _ => continue,
             }
         }
 
-        Self {
-            handlers,
+        let mut result = Self {
+            routes,
+            scopes,
             guards,
             hooks,
             default,
-            raw_routes,
+            lints,
+        };
+        result.check_routes();
+        result
+    }
+
+    ///Checks for routes that conflict with each other.
+    fn check_routes(&mut self) {
+        let resources = self.resources();
+        let mut lints = Vec::new();
+
+        for (path, routes) in resources.iter() {
+            for (idx, route) in routes.iter().enumerate() {
+                if let Some(first) = routes[..idx].iter().find(|prev| prev.guard.method() == route.guard.method()) {
+                    lints.push(format!("Route '{}' duplicates '{}' for {} {}", route.name, first.name, route.guard.method().unwrap_or("any method"), path));
+                }
+            }
+        }
+
+        //Routes of the path share resource, so resource hooks of one route are applied to others
+        for (path, routes) in resources.iter() {
+            let routes = routes.iter().filter(|route| route.attrs.extra_guards.is_empty() && route.attrs.data.is_empty()).collect::<Vec<_>>();
+            if let Some(route) = routes.iter().find(|route| route.attrs.hooks != routes[0].attrs.hooks) {
+                lints.push(format!("Routes '{}' and '{}' share resource for {}, so their resource hooks are applied to both", routes[0].name, route.name, path));
+            }
+        }
+
+        //Resources are matched in order of registration, so catch-all pattern hides everything after it
+        for (idx, (path, _)) in resources.iter().enumerate() {
+            if !is_catch_all(path) {
+                continue;
+            }
+
+            for (shadowed, routes) in resources[idx+1..].iter().filter(|(shadowed, _)| is_catch_all_match(path, shadowed)) {
+                for route in routes.iter() {
+                    lints.push(format!("Route '{}' for {} is unreachable, because it is shadowed by catch-all {}", route.name, shadowed, path));
+                }
+            }
         }
+
+        for lint in lints {
+            self.lints.push(lint);
+        }
+    }
+
+    ///Groups routes by path, preserving order of declaration.
+    fn resources(&self) -> Vec<(&str, Vec<&ScopeRoute>)> {
+        let mut resources: Vec<(&str, Vec<&ScopeRoute>)> = Vec::new();
+
+        for route in self.routes.iter() {
+            match resources.iter_mut().find(|(path, _)| *path == route.path) {
+                Some((_, routes)) => routes.push(route),
+                None => resources.push((route.path.as_str(), vec![route])),
+            }
+        }
+
+        resources
+    }
+
+    ///Writes resource per each unique path.
+    ///
+    ///Handlers are referred as `<handler_prefix>::<name>`, while route's guards are formatted
+    ///using `route_guard`. Resource hooks and data are written as is, same as by route macro.
+    ///
+    ///Routes with own guards are registered as separate resources before the shared one,
+    ///so that requests rejected by guards fall through to other routes of the path.
+    ///
+    ///Unless path contains handler without method guard, resource responds with `405 Method Not
+    ///Allowed` to unexpected methods. When `options` flag is set, resource responds to `OPTIONS` too.
+    ///
+    ///`GET` handlers are registered for `HEAD` as well, if requested by route or `auto_head`.
+    fn write_resources(&self, f: &mut fmt::Formatter, flags: &Flags, handler_prefix: &str, route_guard: &dyn Fn(&str) -> String) -> fmt::Result {
+        for (path, routes) in self.resources() {
+            let (own, routes): (Vec<&ScopeRoute>, Vec<&ScopeRoute>) = routes.into_iter().partition(|route| !route.attrs.extra_guards.is_empty() || !route.attrs.data.is_empty());
+            for route in own {
+                route.write_own_resource(f, flags, handler_prefix, route_guard)?;
+            }
+            if routes.is_empty() {
+                continue;
+            }
+
+            write!(f, "\n        .service(")?;
+            //Resource hooks of every route on the path are applied to shared resource
+            let hooks = routes.iter().flat_map(|route| route.attrs.hooks.iter()).collect::<Vec<_>>();
+            for hook in hooks.iter().rev() {
+                write!(f, "{}(", hook)?;
+            }
+            write!(f, "actix_web::Resource::new({:?})", path)?;
+
+            let mut methods = Some(Vec::new());
+            for route in routes.iter() {
+                match route.guard.method() {
+                    Some(method) => if let Some(methods) = methods.as_mut() {
+                        methods.push(method);
+                        if route.has_head(flags) {
+                            methods.push("HEAD");
+                        }
+                    },
+                    None => methods = None,
+                }
+
+                let method_guard = match route.guard {
+                    GuardType::None => None,
+                    ref guard => Some(guard.to_string()),
+                };
+                route.write_route(f, flags, method_guard.as_ref().map(|guard| &guard[..]), handler_prefix)?;
+                if route.has_head(flags) {
+                    route.write_route(f, flags, Some("Head"), handler_prefix)?;
+                }
+            }
+
+            if let Some(methods) = methods {
+                let mut methods = methods.into_iter().fold(Vec::new(), |mut methods, method| {
+                    if !methods.contains(&method) {
+                        methods.push(method);
+                    }
+                    methods
+                });
+                if flags.options {
+                    methods.push("OPTIONS");
+                }
+                let allow = methods.join(", ");
+
+                if flags.options {
+                    write!(f, "\n            .route(actix_web::Route::new().guard(actix_web::guard::Options()).to(|| actix_web::HttpResponse::Ok().header(\"Allow\", \"{}\").finish()))", allow)?;
+                }
+                write!(f, "\n            .default_resource(|res| res.to(|| actix_web::HttpResponse::MethodNotAllowed().header(\"Allow\", \"{}\").finish()))", allow)?;
+            }
+
+            for _ in hooks.iter() {
+                write!(f, ")")?;
+            }
+            write!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ScopeItems {
+    ///Writes statements, which append own routes of scope to `routes`, prefixing them with `path`.
+    fn write_routes_info(&self, f: &mut fmt::Formatter, flags: &Flags) -> fmt::Result {
+        for route in self.routes.iter() {
+            writeln!(f, "        routes.push((format!(\"{{}}{{}}\", path, {:?}), &{:?}, {:?}));", route.path, route.methods(flags), route.name.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    ///Writes statements, which append OpenAPI operations of scope's routes to `operations`.
+    fn write_operations(&self, f: &mut fmt::Formatter, flags: &Flags) -> fmt::Result {
+        for route in self.routes.iter() {
+            for method in route.methods(flags) {
+                write!(f, "        operations.push(")?;
+                route.operation.write(f, &format!("format!(\"{{}}{{}}\", path, {:?})", route.path), method)?;
+                writeln!(f, ");")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///Function `actix_scope_resources`, which registers resources of scope's routes.
+///
+///It is declared within scope's module, so that handlers, guards, hooks and data of routes
+///are resolved the same way as by route macro.
+struct ModuleResources<'a> {
+    scope_items: &'a ScopeItems,
+    flags: &'a Flags,
+}
+
+impl<'a> fmt::Display for ModuleResources<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "#[doc(hidden)]")?;
+        writeln!(f, "pub(super) fn actix_scope_resources<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {{")?;
+        write!(f, "    scope")?;
+        self.scope_items.write_resources(f, self.flags, "self", &|guard| format!("actix_web::guard::fn_guard({})", guard))?;
+        writeln!(f, "\n}}")
     }
 }
 
 pub struct Args {
-    ast: syn::ItemConst,
-    name: syn::Ident,
+    module: syn::ItemMod,
+    ///Service's name for `const` form, which is declared next to the module.
+    ///Otherwise service is declared inside of module as `scope`.
+    name: Option<syn::Ident>,
+    ///Visibility of service declared next to the module.
+    vis: syn::Visibility,
     path: String,
+    path_env: Option<String>,
+    ///Path to serve API documentation at.
+    docs: Option<String>,
+    ///Path of lock file with route table.
+    lock: Option<String>,
+    flags: Flags,
     items: Items,
     scope_items: ScopeItems,
 }
@@ -87,26 +871,29 @@ impl Args {
             panic!("invalid server definition, expected: #[scope(\"some path\")]");
         }
 
-        let ast: syn::ItemConst = syn::parse(input).expect("Parse input as module");
-        //TODO: we should change it to mod once supported on stable
-        //let ast: syn::ItemMod = syn::parse(input).expect("Parse input as module");
-        let name = ast.ident.clone();
-
-        let mut items = Vec::new();
-        match ast.expr.as_ref() {
-            syn::Expr::Block(expr) => for item in expr.block.stmts.iter() {
-                match item {
-                    syn::Stmt::Item(ref item) => items.push(item.clone()),
-                    _ => continue,
-                }
+        let ast: syn::Item = syn::parse(input).expect("Parse input as module");
+        let mut vis = syn::Visibility::Inherited;
+        let (mut module, name) = match ast {
+            syn::Item::Const(ast) => {
+                vis = ast.vis.clone();
+                let module_name = syn::Ident::new(&format!("{}_scope", ast.ident), ast.ident.span());
+                let module = match ast.expr.as_ref() {
+                    syn::Expr::Block(expr) => syn::parse2(quote!(pub mod #module_name #expr)).expect("Parse scope's code block as module"),
+                    _ => panic!("Scope should containt only code block { }"),
+                };
+                (module, Some(ast.ident))
             },
-            _ => panic!("Scope should containt only code block { }"),
-        }
+            syn::Item::Mod(module) => (module, None),
+            syn::Item::Struct(_) => panic!("Scope cannot be applied to struct. Use #[derive(Scope)] and place #[scope(...)] after it"),
+            _ => panic!("Scope can be applied only to module or const with code block"),
+        };
 
-        let scope_items = ScopeItems::from_items(&items);
         let mut items = Items::default();
-
         let mut path = None;
+        let mut path_env = None;
+        let mut docs = None;
+        let mut lock = None;
+        let mut flags = Flags::default();
         for arg in args {
             match arg {
                 syn::NestedMeta::Literal(syn::Lit::Str(ref fname)) => {
@@ -116,20 +903,45 @@ impl Args {
                     let fname = quote!(#fname).to_string();
                     path = Some(fname.as_str()[1..fname.len() - 1].to_owned())
                 },
+                syn::NestedMeta::Meta(syn::Meta::Word(ident)) => flags.parse(ident),
                 syn::NestedMeta::Meta(syn::Meta::NameValue(ident)) => match ident.ident.to_string().to_lowercase().as_str() {
                     "guard" => match ident.lit {
                         syn::Lit::Str(ref text) => items.guards.push(text.value()),
                         _ => panic!("Attribute guard expects literal string!"),
                     },
                     "hook" => match ident.lit {
-                        syn::Lit::Str(ref text) => items.hooks.push(text.value()),
+                        syn::Lit::Str(ref text) => items.hooks.push(ScopeHook::new(text.value())),
                         _ => panic!("Attribute hook expects literal string!"),
                     },
                     "handler" => match ident.lit {
                         syn::Lit::Str(ref text) => items.handlers.push(text.value()),
                         _ => panic!("Attribute guard expects literal string!"),
                     },
-                    attr => panic!("Unknown attribute key is specified: {}. Allowed: guard, hook, handler", attr)
+                    "scope" => match ident.lit {
+                        syn::Lit::Str(ref text) => items.scopes.push(text.value()),
+                        _ => panic!("Attribute scope expects literal string!"),
+                    },
+                    "path_env" => match ident.lit {
+                        syn::Lit::Str(ref text) => path_env = Some(text.value()),
+                        _ => panic!("Attribute path_env expects literal string!"),
+                    },
+                    "data" => match ident.lit {
+                        syn::Lit::Str(ref text) => items.data.push(text.value()),
+                        _ => panic!("Attribute data expects literal string!"),
+                    },
+                    "tag" => match ident.lit {
+                        syn::Lit::Str(ref text) => items.tags.push(text.value()),
+                        _ => panic!("Attribute tag expects literal string!"),
+                    },
+                    "docs" => match ident.lit {
+                        syn::Lit::Str(ref text) => docs = Some(text.value()),
+                        _ => panic!("Attribute docs expects literal string!"),
+                    },
+                    "lock" => match ident.lit {
+                        syn::Lit::Str(ref text) => lock = Some(text.value()),
+                        _ => panic!("Attribute lock expects literal string!"),
+                    },
+                    attr => panic!("Unknown attribute key is specified: {}. Allowed: guard, hook, handler, scope, path_env, data, tag, docs, lock", attr)
                 },
                 attr => panic!("Unknown attribute{:?}", attr)
             }
@@ -137,10 +949,122 @@ impl Args {
 
         let path = path.expect("Scope's path is not specified!");
 
+        let (mut scope_items, resolver) = match module.content {
+            Some((_, ref items)) => {
+                let resolver = AttrResolver::from_items(items);
+                (ScopeItems::from_items(items, &flags, &resolver), resolver)
+            },
+            None => panic!("Scope cannot access content of module '{}'. Declare module inline or enable proc_macro_hygiene to load it from file", module.ident),
+        };
+        scope_items.lints.finish(&flags);
+
+        if let Some((_, ref content)) = module.content {
+            let mut entries = Vec::new();
+            let mut functions = Vec::new();
+            let scope = RouteScope {
+                path: &path,
+                name_prefix: "",
+                guards: &items.guards,
+                flags: &flags,
+            };
+            walk_routes(&scope, content, &resolver, &mut |scope, route| {
+                let mut guards = scope.guards.to_vec();
+                guards.extend(route.attrs.extra_guards.iter().cloned());
+                entries.push(manifest::Entry {
+                    path: format!("{}{}", scope.path, route.path),
+                    methods: route.methods(scope.flags),
+                    name: route.name.to_string(),
+                    guards,
+                    location: route.location.clone(),
+                });
+
+                if let Some(ref function) = route.typescript {
+                    functions.push((scope.name_prefix.to_owned(), scope.path.to_owned(), function.clone()));
+                }
+            });
+            let scope_name = name.as_ref().unwrap_or(&module.ident).to_string();
+            manifest::write(&scope_name, &entries, flags.manifest);
+            typescript::write_module(&scope_name, &functions);
+
+            //New routes are reported after `finish`, as they are not problems
+            if let Some(ref lock) = lock {
+                for route in lock::check(lock, &scope_name, &entries) {
+                    scope_items.lints.push(format!("Route '{}' is not in {}. Regenerate it with CUTE_CODEGEN_UPDATE_LOCK=1", route, lock));
+                }
+            }
+        }
+        let lock = lock.map(|lock| lock::path(&lock).display().to_string());
+
+        for route in scope_items.routes.iter_mut() {
+            route.handler = syn::Ident::new(&format!("__actix_route_{}", route.name), route.name.span());
+        }
+
+        if let Some((_, ref mut content)) = module.content {
+            let mut services = Vec::new();
+            //Routes are registered by scope itself, so their functions are renamed,
+            //while routes keep their own services to be registered separately.
+            for item in content.iter_mut() {
+                match item {
+                    syn::Item::Fn(ref mut fun) => {
+                        if let Some(route) = scope_items.routes.iter().find(|route| route.name == fun.ident) {
+                            services.push(route::Args::member(fun.clone(), &route.args, route.guard, route.handler.clone()).to_string());
+                            fun.ident = route.handler.clone();
+                            fun.attrs.push(syn::parse_quote!(#[doc(hidden)]));
+                        }
+                        fun.attrs.retain(|attr| !resolver.is_used(attr));
+                    },
+                    //Nested scopes are registered by outer scope
+                    syn::Item::Mod(ref mut module) if module.attrs.iter().any(|attr| resolver.is_scope(attr)) => if let syn::Visibility::Inherited = module.vis {
+                        module.vis = syn::parse_str("pub(super)").expect("Parse visibility");
+                    },
+                    syn::Item::Const(ref mut module) if module.attrs.iter().any(|attr| resolver.is_scope(attr)) => if let syn::Visibility::Inherited = module.vis {
+                        module.vis = syn::parse_str("pub(super)").expect("Parse visibility");
+                    },
+                    //Unconsumed attributes are reported as lints
+                    syn::Item::Const(ref mut item) => item.attrs.retain(|attr| !resolver.is_used(attr)),
+                    syn::Item::Static(ref mut item) => item.attrs.retain(|attr| !resolver.is_used(attr)),
+                    syn::Item::Struct(ref mut item) => item.attrs.retain(|attr| !resolver.is_used(attr)),
+                    //Macros are consumed by scope, so their imports may end up unused
+                    syn::Item::Use(ref mut item) => match item.tree {
+                        syn::UseTree::Path(ref path) if path.ident == CRATE_NAME => item.attrs.push(syn::parse_quote!(#[allow(unused_imports)])),
+                        _ => continue,
+                    },
+                    _ => continue,
+                }
+            }
+
+            for service in services {
+                let service: syn::File = syn::parse_str(&service).expect("Parse route's service");
+                content.extend(service.items);
+            }
+
+            let resources = ModuleResources {
+                scope_items: &scope_items,
+                flags: &flags,
+            };
+            content.push(syn::parse_str(&resources.to_string()).expect("Parse scope's resources"));
+
+            if name.is_none() {
+                content.push(syn::parse_quote!(#[allow(non_camel_case_types)] pub struct scope;));
+            }
+
+            if flags.client {
+                let methods = scope_items.routes.iter().filter_map(|route| route.client.as_ref()).collect();
+                let client = client::Client::new("Client".to_owned(), &path, path_expr(&path, path_env.as_deref()), methods);
+                let client: syn::File = syn::parse_str(&client.to_string()).expect("Parse scope's client");
+                content.extend(client.items);
+            }
+        }
+
         Self {
-            ast,
+            module,
             name,
+            vis,
             path,
+            path_env,
+            docs,
+            lock,
+            flags,
             items,
             scope_items,
         }
@@ -159,29 +1083,41 @@ impl Args {
 
 impl fmt::Display for Args {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let ast = &self.ast;
+        let module = &self.module;
+        let module_name = &module.ident;
 
-        let module_name = format!("{}_scope", self.name);
-        let module_name = syn::Ident::new(&module_name, ast.ident.span());
-        let ast = match ast.expr.as_ref() {
-            syn::Expr::Block(expr) => quote!(pub mod #module_name #expr),
-            _ => panic!("Unexpect non-block ast in scope macro")
+        writeln!(f, "{}\n", quote!(#module))?;
+        let name = match self.name {
+            Some(ref name) => {
+                writeln!(f, "#[allow(non_camel_case_types)]")?;
+                let vis = &self.vis;
+                writeln!(f, "{} struct {};\n", quote!(#vis), name)?;
+                name.to_string()
+            },
+            None => format!("{}::scope", module_name),
         };
+        let hooks = sorted_hooks(self.items.hooks.iter().map(|hook| (hook.name.clone(), hook))
+                                 .chain(self.scope_items.hooks.iter().map(|hook| (format!("{}::{}", module_name, hook.name), hook))));
 
-        writeln!(f, "{}\n", ast)?;
-        writeln!(f, "#[allow(non_camel_case_types)]")?;
-        writeln!(f, "struct {};\n", self.name)?;
-        writeln!(f, "impl<P: 'static> actix_web::dev::HttpServiceFactory<P> for {} {{", self.name)?;
+        writeln!(f, "impl<P: 'static> actix_web::dev::HttpServiceFactory<P> for {} {{", name)?;
         writeln!(f, "    fn register(self, config: &mut actix_web::dev::ServiceConfig<P>) {{")?;
-        write!(f, "        let scope = actix_web::Scope::new(\"{}\")", self.path)?;
+        write!(f, "{}", self.scope_items.lints)?;
+        //Makes compiler to track changes of lock
+        if let Some(ref lock) = self.lock {
+            writeln!(f, "        let _ = include_str!({:?});", lock)?;
+        }
 
-        for hook in self.items.hooks.iter() {
-            write!(f, ";\n        let scope = {}(scope)", hook)?;
+        for (hook, _) in hooks.iter().filter(|(_, hook)| hook.config && !hook.after_services) {
+            writeln!(f, "        {}(config);", hook)?;
         }
-        for hook in self.scope_items.hooks.iter() {
-            write!(f, ";\n        let scope = {}::{}(scope)", module_name, hook)?;
+
+        writeln!(f, "        let path = {};", path_expr(&self.path, self.path_env.as_ref().map(|path_env| &path_env[..])))?;
+        if self.docs.is_some() {
+            writeln!(f, "        let docs = {}::openapi_with_path(path);", name)?;
         }
 
+        write!(f, "        let scope = actix_web::Scope::new(path)")?;
+
         for guard in self.items.guards.iter() {
             write!(f, ".guard(actix_web::guard::fn_guard({}))", guard)?;
         }
@@ -189,54 +1125,169 @@ impl fmt::Display for Args {
             write!(f, ".guard(actix_web::guard::fn_guard({}::{}))", module_name, guard)?;
         }
 
+        for data in self.items.data.iter() {
+            write!(f, ".data({})", data)?;
+        }
+
+        for (hook, _) in hooks.iter().filter(|(_, hook)| !hook.config && !hook.after_services) {
+            write!(f, ";\n        let scope = {}(scope)", hook)?;
+        }
+
+        write!(f, ";\n        let scope = scope")?;
         for handler in self.items.handlers.iter() {
             write!(f, ".service({})", handler)?;
         }
-        for handler in self.scope_items.handlers.iter() {
-            write!(f, ".service({}::{})", module_name, handler)?;
+        write!(f, ";\n        let scope = {}::actix_scope_resources(scope)", module_name)?;
+
+        for scope in self.items.scopes.iter() {
+            write!(f, ".service({})", scope)?;
+        }
+        for scope in self.scope_items.scopes.iter() {
+            write!(f, ".service({}::{})", module_name, scope)?;
+        }
+
+        if let Some(docs) = self.docs.as_ref() {
+            crate::docs::write_services(f, docs)?;
+        }
+
+        for (hook, _) in hooks.iter().filter(|(_, hook)| !hook.config && hook.after_services) {
+            write!(f, ";\n        let scope = {}(scope)", hook)?;
         }
 
+        //Default service is registered last, so that it doesn't shadow services of hooks
         if let Some(default) = self.scope_items.default.as_ref() {
-            write!(f, ".default_resource({}::{})", module_name, default)?;
+            default.write(f, &module_name.to_string())?;
         }
 
         writeln!(f, ";\n")?;
-        writeln!(f, "        actix_web::dev::HttpServiceFactory::register(scope, config)")?;
-        writeln!(f, "    }}\n}}")
+        writeln!(f, "        actix_web::dev::HttpServiceFactory::register(scope, config);")?;
+
+        for (hook, _) in hooks.iter().filter(|(_, hook)| hook.config && hook.after_services) {
+            writeln!(f, "        {}(config);", hook)?;
+        }
+
+        writeln!(f, "    }}\n}}\n")?;
+
+        let path = path_expr(&self.path, self.path_env.as_ref().map(|path_env| &path_env[..]));
+        writeln!(f, "impl {} {{", name)?;
+        writeln!(f, "    ///Returns routes of scope with full paths.")?;
+        writeln!(f, "    #[allow(dead_code)]")?;
+        writeln!(f, "    pub fn routes() -> {} {{", ROUTES_TYPE)?;
+        writeln!(f, "        Self::routes_with_path({})", path)?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Returns routes of scope, which is served at `path`.")?;
+        writeln!(f, "    #[allow(dead_code, unused_mut)]")?;
+        writeln!(f, "    pub fn routes_with_path(path: &str) -> {} {{", ROUTES_TYPE)?;
+        write_routes_fallback(f)?;
+        writeln!(f, "        let mut routes: {} = Vec::new();", ROUTES_TYPE)?;
+        self.scope_items.write_routes_info(f, &self.flags)?;
+        for handler in self.items.handlers.iter() {
+            write_nested_routes(f, handler)?;
+        }
+        for scope in self.items.scopes.iter() {
+            write_nested_routes(f, scope)?;
+        }
+        for scope in self.scope_items.scopes.iter() {
+            write_nested_routes(f, &format!("{}::{}", module_name, scope))?;
+        }
+        writeln!(f, "        routes")?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Returns OpenAPI operations of scope with full paths.")?;
+        writeln!(f, "    #[allow(dead_code)]")?;
+        writeln!(f, "    pub fn openapi_operations() -> {} {{", OPERATIONS_TYPE)?;
+        writeln!(f, "        Self::openapi_operations_with_path({})", path)?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Returns OpenAPI operations of scope, which is served at `path`.")?;
+        writeln!(f, "    #[allow(dead_code, unused_mut)]")?;
+        writeln!(f, "    pub fn openapi_operations_with_path(path: &str) -> {} {{", OPERATIONS_TYPE)?;
+        write_routes_fallback(f)?;
+        writeln!(f, "        let mut operations: {} = Vec::new();", OPERATIONS_TYPE)?;
+        self.scope_items.write_operations(f, &self.flags)?;
+        for handler in self.items.handlers.iter() {
+            write_nested_operations(f, handler)?;
+        }
+        for scope in self.items.scopes.iter() {
+            write_nested_operations(f, scope)?;
+        }
+        for scope in self.scope_items.scopes.iter() {
+            write_nested_operations(f, &format!("{}::{}", module_name, scope))?;
+        }
+        write_operations_tags(f, &self.items.tags)?;
+        writeln!(f, "        operations")?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    #[allow(dead_code)]")?;
+        openapi::write_document(f)?;
+        writeln!(f)?;
+        writeln!(f, "    #[allow(dead_code)]")?;
+        openapi::write_document_with_path(f)?;
+
+        if self.flags.client {
+            writeln!(f, "\n    ///Creates HTTP client of scope, which is served at `base_url`.")?;
+            writeln!(f, "    #[allow(dead_code)]")?;
+            writeln!(f, "    pub fn client(base_url: &str) -> {}::Client {{", module_name)?;
+            writeln!(f, "        {}::Client::new(base_url)", module_name)?;
+            writeln!(f, "    }}")?;
+        }
+        writeln!(f, "}}\n")?;
+        write_scope_info(f, "impl", &name, "")?;
+
+        if self.flags.smoke_test {
+            smoke::write_smoke_test(f, &name, &|f| {
+                writeln!(f, "        let path = {};", path)?;
+                self.scope_items.write_routes_info(f, &self.flags)
+            })?;
+        }
+
+        Ok(())
     }
 }
 
 pub struct ImplScope {
     ast: syn::ItemImpl,
-    name: String,
+    flags: Flags,
     scope_items: ScopeItems,
 }
 
 impl ImplScope {
-    pub fn new(input: TokenStream) -> Self {
+    pub fn new(args: &Vec<syn::NestedMeta>, input: TokenStream) -> Self {
         let mut ast: syn::ItemImpl = syn::parse(input).expect("Parse input as impl block");
 
-        let name = match &*ast.self_ty {
-            &syn::Type::Path(ref type_path) => {
-                if type_path.path.segments.len() > 1 {
-                    panic!("Too many type params in path!");
-                }
-
-                let item = type_path.path.segments.first().expect("To have at least one path segment in Self type");
-                item.value().ident.to_string()
-            },
+        match &*ast.self_ty {
+            &syn::Type::Path(_) => (),
             _ => panic!("Scope can be implemented only for impl Block")
-        };
+        }
 
-        let mut scope_items: Vec<syn::Item> = Vec::new();
+        if let Some((_, ref path, _)) = ast.trait_ {
+            let self_ty = &ast.self_ty;
+            let trait_name = path.segments.last().map(|segment| segment.value().ident.to_string()).unwrap_or_default();
+            panic!("Scope cannot be declared by impl of trait '{}'. Declare routes on trait with #[scope(\"path\")] and serve it by {}Scope({})", quote!(#path), trait_name, quote!(#self_ty));
+        }
 
-        let mut used_attrs = HashSet::new();
-        used_attrs.insert("get");
-        used_attrs.insert("put");
-        used_attrs.insert("delete");
-        used_attrs.insert("handler");
-        used_attrs.insert("hook");
-        used_attrs.insert("guard");
+        let mut flags = Flags::default();
+        for arg in args {
+            match arg {
+                syn::NestedMeta::Meta(syn::Meta::Word(ident)) => flags.parse(ident),
+                attr => panic!("Unknown attribute{:?}", attr)
+            }
+        }
+        if flags.manifest {
+            panic!("Scope declared by impl block doesn't support 'manifest'. Declare scope by module instead");
+        }
+        if flags.smoke_test {
+            panic!("Scope declared by impl block doesn't support 'smoke_test'. Declare scope by module instead");
+        }
+        if flags.client {
+            panic!("Scope declared by impl block doesn't support 'client'. Declare scope by module or trait instead");
+        }
+
+        let mut scope_items: Vec<syn::Item> = Vec::new();
+        let mut lints = Lints::default();
+        //Impl block cannot contain imports, so only names and full paths of macros are recognized
+        let resolver = AttrResolver::default();
 
         for item in ast.items.iter_mut() {
             match item {
@@ -254,25 +1305,29 @@ impl ImplScope {
                     };
 
                     scope_items.push(syn::Item::Fn(item));
-                    method.attrs.retain(|attr| {
-                        for bound in attr.path.segments.iter() {
-                            if used_attrs.contains(bound.ident.to_string().as_str()) {
-                                return false;
-                            }
-                        }
-
-                        true
-                    })
+                    method.attrs.retain(|attr| !resolver.is_used(attr));
+                },
+                syn::ImplItem::Const(item) => {
+                    lints.check_unconsumed(&resolver, "const", &item.ident, &item.attrs);
+                    item.attrs.retain(|attr| !resolver.is_used(attr));
                 },
                 _ => continue
             }
         }
 
-        let scope_items = ScopeItems::from_items(&scope_items);
+        let mut scope_items = ScopeItems::from_items(&scope_items, &flags, &resolver);
+        for lint in lints.messages {
+            scope_items.lints.push(lint);
+        }
+        scope_items.lints.finish(&flags);
+
+        if let Some(hook) = scope_items.hooks.iter().find(|hook| hook.config) {
+            panic!("Hook '{}' with config is not supported within impl block", hook.name);
+        }
 
         Self {
             ast,
-            name,
+            flags,
             scope_items,
         }
     }
@@ -292,77 +1347,241 @@ impl fmt::Display for ImplScope {
         let ast = &self.ast;
         writeln!(f, "{}\n", quote!(#ast))?;
 
-        writeln!(f, "impl<P: 'static> actix_web::dev::HttpServiceFactory<P> for {} {{", self.name)?;
-        writeln!(f, "    fn register(self, config: &mut actix_web::dev::ServiceConfig<P>) {{")?;
+        let param = service_param(&ast.generics);
+        let (impl_gen, _, where_clause) = ast.generics.split_for_impl();
+        let self_ty = &ast.self_ty;
 
-        write!(f, "        let scope = self.actix_scope()")?;
+        writeln!(f, "{} {} {} {{", quote!(impl#impl_gen), quote!(#self_ty), quote!(#where_clause))?;
+        writeln!(f, "    #[doc(hidden)]")?;
+        writeln!(f, "    pub fn actix_scope_register<{}: 'static>(self, path: &str) -> actix_web::Scope<{}> {{", param, param)?;
+        write!(f, "{}", self.scope_items.lints)?;
 
-        for hook in self.scope_items.hooks.iter() {
-            write!(f, ";\n        let scope = Self::{}(scope)", hook)?;
+        let by_ref_routes = self.scope_items.routes.iter().any(|route| route.by_ref.is_some());
+        if by_ref_routes || self.scope_items.hooks.iter().any(|hook| hook.by_ref) {
+            writeln!(f, "        let this = std::clone::Clone::clone(&self);")?;
         }
 
+        write!(f, "        let scope = self.actix_scope_with_path(path)")?;
+
         for guard in self.scope_items.guards.iter() {
             write!(f, ".guard(actix_web::guard::fn_guard(Self::{}))", guard)?;
         }
 
-        for fun in self.scope_items.raw_routes.iter() {
-            let mut guard = crate::route::GuardType::None;
-            let mut attrs = Vec::new();
+        if by_ref_routes {
+            write!(f, ".data(std::clone::Clone::clone(&this))")?;
+        }
 
-            for attr in fun.attrs.iter() {
-                let is_found = if attr.path.is_ident("get") {
-                    guard = crate::route::GuardType::Get;
-                    true
-                } else if attr.path.is_ident("put") {
-                    guard = crate::route::GuardType::Put;
-                    true
-                } else if attr.path.is_ident("post") {
-                    guard = crate::route::GuardType::Post;
-                    true
-                } else if attr.path.is_ident("delete") {
-                    guard = crate::route::GuardType::Delete;
-                    true
-                } else if attr.path.is_ident("hanlder") {
-                    true
-                } else {
-                    false
-                };
+        let hooks = sorted_hooks(self.scope_items.hooks.iter().map(|hook| match hook.by_ref {
+            true => (format!("this.{}", hook.name), hook),
+            false => (format!("Self::{}", hook.name), hook),
+        }));
 
-                if is_found {
-                    let args: proc_macro::TokenStream = attr.tts.clone().into();
-                    let args = match args.into_iter().next().expect("To have single token tree in route's args") {
-                        proc_macro::TokenTree::Group(group) => group.stream(),
-                        args => panic!("Expected route's args as TokenTree's Group, but got {:?}", args),
-                    };
-                    //That's hidden API, dangerous?
-                    let mut args = syn::parse_macro_input::parse::<syn::AttributeArgs>(args).expect("To parse route's arguments");
-                    mem::swap(&mut args, &mut attrs);
-                    break;
-                }
-            }
+        for (hook, _) in hooks.iter().filter(|(_, hook)| !hook.after_services) {
+            write!(f, ";\n        let scope = {}(scope)", hook)?;
+        }
+
+        write!(f, ";\n        let scope = scope")?;
+        self.scope_items.write_resources(f, &self.flags, "Self", &|guard| format!("self.{}", guard))?;
 
-            let (path, resource_type, extra_guards) = crate::route::parse_meta_attrs(&fun, &attrs);
-            let path = path.expect("Route's handle misses path");
-            write!(f, ".route(\"{}\", actix_web::Route::new()", path)?;
+        for (hook, _) in hooks.iter().filter(|(_, hook)| hook.after_services) {
+            write!(f, ";\n        let scope = {}(scope)", hook)?;
+        }
+
+        if let Some(default) = self.scope_items.default.as_ref() {
+            default.write(f, "Self")?;
+        }
+
+        writeln!(f, ";\n")?;
+        writeln!(f, "        scope")?;
+        writeln!(f, "    }}\n")?;
 
-            if guard != GuardType::None {
-                write!(f, ".guard(actix_web::guard::{}())", guard)?;
+        writeln!(f, "    #[doc(hidden)]")?;
+        writeln!(f, "    #[allow(unused_mut, unused_variables)]")?;
+        writeln!(f, "    pub fn actix_scope_routes(path: &str) -> {} {{", ROUTES_TYPE)?;
+        writeln!(f, "        let mut routes: {} = Vec::new();", ROUTES_TYPE)?;
+        self.scope_items.write_routes_info(f, &self.flags)?;
+        writeln!(f, "        routes")?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    #[doc(hidden)]")?;
+        writeln!(f, "    #[allow(unused_mut, unused_variables)]")?;
+        writeln!(f, "    pub fn actix_scope_openapi(path: &str) -> {} {{", OPERATIONS_TYPE)?;
+        writeln!(f, "        let mut operations: {} = Vec::new();", OPERATIONS_TYPE)?;
+        self.scope_items.write_operations(f, &self.flags)?;
+        writeln!(f, "        operations")?;
+        writeln!(f, "    }}\n}}")
+    }
+}
+
+///Scope declared by trait, which is served by any of its implementations.
+pub struct TraitScope {
+    ast: syn::ItemTrait,
+    path: String,
+    path_env: Option<String>,
+    flags: Flags,
+    scope_items: ScopeItems,
+}
+
+impl TraitScope {
+    pub fn new(args: &Vec<syn::NestedMeta>, input: TokenStream) -> Self {
+        let mut ast: syn::ItemTrait = syn::parse(input).expect("Parse input as trait");
+
+        if !ast.generics.params.is_empty() {
+            panic!("Scope cannot be declared by generic trait");
+        }
+
+        let mut path = None;
+        let mut path_env = None;
+        let mut flags = Flags::default();
+        for arg in args {
+            match arg {
+                syn::NestedMeta::Literal(syn::Lit::Str(ref text)) => {
+                    if path.is_some() {
+                        panic!("Multiple paths specified! Should be only one!")
+                    }
+                    path = Some(text.value())
+                },
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref ident)) if ident.ident == "path_env" => match ident.lit {
+                    syn::Lit::Str(ref text) => path_env = Some(text.value()),
+                    _ => panic!("Attribute path_env expects literal string!"),
+                },
+                syn::NestedMeta::Meta(syn::Meta::Word(ident)) => flags.parse(ident),
+                attr => panic!("Unknown attribute{:?}", attr)
             }
+        }
+        let path = path.expect("Scope's path is not specified!");
+        if flags.manifest || flags.smoke_test {
+            panic!("Scope declared by trait '{}' doesn't support 'manifest' and 'smoke_test'", ast.ident);
+        }
+
+        let mut methods = Vec::new();
+        let mut lints = Lints::default();
+        //Trait cannot contain imports, so only names and full paths of macros are recognized
+        let resolver = AttrResolver::default();
 
-            for guard in extra_guards.iter() {
-                write!(f, ".guard(self.{})", guard)?;
+        for item in ast.items.iter_mut() {
+            match item {
+                syn::TraitItem::Method(method) => {
+                    let item = syn::ItemFn {
+                        attrs: method.attrs.clone(),
+                        vis: syn::Visibility::Inherited,
+                        constness: method.sig.constness,
+                        unsafety: method.sig.unsafety,
+                        asyncness: method.sig.asyncness,
+                        abi: method.sig.abi.clone(),
+                        ident: method.sig.ident.clone(),
+                        decl: Box::new(method.sig.decl.clone()),
+                        block: Box::new(method.default.clone().unwrap_or_else(|| syn::parse_quote!({}))),
+                    };
+
+                    methods.push(item);
+                    method.attrs.retain(|attr| !resolver.is_used(attr));
+                },
+                syn::TraitItem::Const(item) => {
+                    lints.check_unconsumed(&resolver, "const", &item.ident, &item.attrs);
+                    item.attrs.retain(|attr| !resolver.is_used(attr));
+                },
+                _ => continue
             }
+        }
 
-            writeln!(f, ".{}(Self::{}))", resource_type, fun.ident)?;
+        let items = methods.iter().cloned().map(syn::Item::Fn).collect::<Vec<_>>();
+        let mut scope_items = ScopeItems::from_items(&items, &flags, &resolver);
+        for lint in lints.messages {
+            scope_items.lints.push(lint);
         }
+        scope_items.lints.finish(&flags);
 
-        if let Some(default) = self.scope_items.default.as_ref() {
-            write!(f, ".default_resource(Self::{})", default)?;
+        if !scope_items.hooks.is_empty() || !scope_items.guards.is_empty() || !scope_items.scopes.is_empty() || scope_items.default.is_some() {
+            panic!("Scope declared by trait '{}' supports only routes", ast.ident);
+        }
+
+        let functions = scope_items.routes.iter().filter_map(|route| route.typescript.clone()).map(|function| (String::new(), path.clone(), function)).collect::<Vec<_>>();
+        typescript::write_module(&ast.ident.to_string(), &functions);
+
+        Self {
+            ast,
+            path,
+            path_env,
+            flags,
+            scope_items,
         }
+    }
+
+    pub fn generate(&self) -> TokenStream {
+        let text = self.to_string();
+
+        match text.parse() {
+            Ok(res) => res,
+            Err(error) => panic!("Error: {:?}\nGenerated code: {}", error, text)
+        }
+    }
+}
 
+impl fmt::Display for TraitScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ast = &self.ast;
+        writeln!(f, "{}\n", quote!(#ast))?;
+
+        let name = &ast.ident;
+        let vis = &ast.vis;
+        let scope = format!("{}Scope", name);
+
+        writeln!(f, "///Service of `{}` scope, which is served by its implementation.", name)?;
+        writeln!(f, "{} struct {}<T>(pub T);\n", quote!(#vis), scope)?;
+
+        writeln!(f, "impl<T> std::ops::Deref for {}<T> {{", scope)?;
+        writeln!(f, "    type Target = T;\n")?;
+        writeln!(f, "    fn deref(&self) -> &T {{")?;
+        writeln!(f, "        &self.0")?;
+        writeln!(f, "    }}")?;
+        writeln!(f, "}}\n")?;
+
+        writeln!(f, "impl<P: 'static, T: {} + 'static> actix_web::dev::HttpServiceFactory<P> for {}<T> {{", name, scope)?;
+        writeln!(f, "    fn register(self, config: &mut actix_web::dev::ServiceConfig<P>) {{")?;
+        write!(f, "{}", self.scope_items.lints)?;
+        let path = path_expr(&self.path, self.path_env.as_deref());
+        write!(f, "        let scope = actix_web::Scope::new({})", path)?;
+        //`&self` handlers are called on instance, which dereferences to implementation
+        if self.scope_items.routes.iter().any(|route| route.by_ref.is_some()) {
+            write!(f, ".data(self)")?;
+        }
+        self.scope_items.write_resources(f, &self.flags, &format!("<T as {}>", name), &|guard| format!("actix_web::guard::fn_guard({})", guard))?;
         writeln!(f, ";\n")?;
-        writeln!(f, "        actix_web::dev::HttpServiceFactory::register(scope, config)")?;
+        writeln!(f, "        actix_web::dev::HttpServiceFactory::register(scope, config);")?;
+        writeln!(f, "    }}")?;
+        writeln!(f, "}}\n")?;
 
-        writeln!(f, "    }}\n}}")
+        writeln!(f, "#[allow(dead_code)]")?;
+        writeln!(f, "impl<T> {}<T> {{", scope)?;
+        writeln!(f, "    ///Returns routes of scope with full paths.")?;
+        writeln!(f, "    #[allow(unused_mut)]")?;
+        writeln!(f, "    pub fn routes() -> {} {{", ROUTES_TYPE)?;
+        writeln!(f, "        let path = {};", path)?;
+        writeln!(f, "        let mut routes: {} = Vec::new();", ROUTES_TYPE)?;
+        self.scope_items.write_routes_info(f, &self.flags)?;
+        writeln!(f, "        routes")?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Returns OpenAPI operations of scope with full paths.")?;
+        writeln!(f, "    #[allow(unused_mut)]")?;
+        writeln!(f, "    pub fn openapi_operations() -> {} {{", OPERATIONS_TYPE)?;
+        writeln!(f, "        let path = {};", path)?;
+        writeln!(f, "        let mut operations: {} = Vec::new();", OPERATIONS_TYPE)?;
+        self.scope_items.write_operations(f, &self.flags)?;
+        writeln!(f, "        operations")?;
+        writeln!(f, "    }}\n")?;
+        openapi::write_document(f)?;
+        writeln!(f, "}}\n")?;
+        write_scope_info(f, "impl<T>", &format!("{}<T>", scope), "")?;
+
+        if self.flags.client {
+            let methods = self.scope_items.routes.iter().filter_map(|route| route.client.as_ref()).collect();
+            let mut client = client::Client::new(format!("{}Client", name), &self.path, path, methods);
+            client.companion = Some(format!("{}Async", name));
+            write!(f, "{}", client)?;
+        }
+
+        Ok(())
     }
 }
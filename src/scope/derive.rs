@@ -3,10 +3,160 @@ use proc_macro::TokenStream;
 
 use std::fmt;
 
-pub struct Args {
+use crate::route::ROUTES_TYPE;
+use crate::openapi::{self, OPERATIONS_TYPE};
+use super::{path_expr, service_param, write_routes_fallback, write_nested_routes, write_scope_info, write_nested_operations, write_operations_tags};
+
+///Container attribute `#[scope(...)]`
+struct Container {
     path: String,
-    service: Vec<String>,
+    path_env: Option<String>,
+    guards: Vec<String>,
+    wrap: Vec<String>,
+    default: Option<String>,
+    tags: Vec<String>,
+    ///Path to serve API documentation at.
+    docs: Option<String>,
+}
+
+impl Container {
+    fn from_attrs(attrs: &[syn::Attribute]) -> Self {
+        let mut path = None;
+        let mut path_env = None;
+        let mut guards = Vec::new();
+        let mut wrap = Vec::new();
+        let mut default = None;
+        let mut tags = Vec::new();
+        let mut docs = None;
+        let mut is_found = false;
+
+        for attr in attrs.iter().flat_map(|attr| attr.parse_meta().ok()) {
+            if attr.name() == "path" {
+                panic!("'path' attribute is no longer supported by derive(Scope). Use #[scope(path = \"/path\")] instead");
+            } else if attr.name() != "scope" {
+                continue;
+            }
+
+            if is_found {
+                panic!("Multiple 'scope' attributes specified! Should be only one!");
+            }
+            is_found = true;
+
+            let list = match attr {
+                syn::Meta::List(list) => list,
+                _ => panic!("'scope' attribute is invalid, expected: #[scope(path = \"/path\")]"),
+            };
+
+            for meta in list.nested.iter() {
+                let meta = match meta {
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(meta)) => meta,
+                    meta => panic!("'scope' attribute contains invalid item: {}. Expected key = \"value\"", quote!(#meta)),
+                };
+
+                let value = match meta.lit {
+                    syn::Lit::Str(ref text) => text.value(),
+                    _ => panic!("'scope' attribute's key '{}' expects literal string!", meta.ident),
+                };
+
+                match meta.ident.to_string().as_str() {
+                    "path" => match path {
+                        Some(_) => panic!("Multiple paths specified! Should be only one!"),
+                        None => path = Some(value),
+                    },
+                    "path_env" => match path_env {
+                        Some(_) => panic!("Multiple path_env specified! Should be only one!"),
+                        None => path_env = Some(value),
+                    },
+                    "guard" => guards.push(value),
+                    "wrap" => wrap.push(value),
+                    "tag" => tags.push(value),
+                    "docs" => match docs {
+                        Some(_) => panic!("Multiple docs paths specified! Should be only one!"),
+                        None => docs = Some(value),
+                    },
+                    "default" => match default {
+                        Some(_) => panic!("Multiple default resources specified! Should be only one!"),
+                        None => default = Some(value),
+                    },
+                    unknown => panic!("Unknown key '{}' of 'scope' attribute. Allowed: path, path_env, guard, wrap, default, tag, docs", unknown),
+                }
+            }
+        }
+
+        let path = match path {
+            Some(path) => path,
+            None => panic!("derive(Scope) requires scope's path, expected: #[scope(path = \"/path\")]"),
+        };
+
+        if !path.is_empty() && !path.starts_with('/') {
+            panic!("Scope's path '{}' should start with '/'", path);
+        }
+
+        Self {
+            path,
+            path_env,
+            guards,
+            wrap,
+            default,
+            tags,
+            docs,
+        }
+    }
+}
+
+///Describes how many values of service field holds.
+enum Multiplicity {
+    Single,
+    ///`Option<S>`, registered only when `Some`
+    Optional,
+    ///`Vec<S>`, each element is registered
+    Repeated,
+}
+
+impl Multiplicity {
+    fn from_type(typ: &syn::Type) -> Self {
+        match typ {
+            syn::Type::Path(ref typ) => match typ.path.segments.iter().last() {
+                Some(segment) if segment.ident == "Option" => Multiplicity::Optional,
+                Some(segment) if segment.ident == "Vec" => Multiplicity::Repeated,
+                _ => Multiplicity::Single,
+            },
+            _ => Multiplicity::Single,
+        }
+    }
+
+    ///Returns type of service stored in field.
+    fn service_type(&self, typ: &syn::Type) -> String {
+        let segment = match (self, typ) {
+            (Multiplicity::Single, _) => None,
+            (_, syn::Type::Path(ref typ)) => typ.path.segments.iter().last(),
+            _ => None,
+        };
+
+        match segment.map(|segment| &segment.arguments) {
+            Some(syn::PathArguments::AngleBracketed(ref args)) => match args.args.iter().next() {
+                Some(syn::GenericArgument::Type(ref typ)) => quote!(#typ).to_string(),
+                _ => quote!(#typ).to_string(),
+            },
+            _ => quote!(#typ).to_string(),
+        }
+    }
+}
+
+struct Service {
+    member: String,
+    ///Type of service, used to collect its routes.
+    typ: String,
+    multiplicity: Multiplicity,
+}
+
+pub struct Args {
+    container: Container,
+    service: Vec<Service>,
     guards: Vec<String>,
+    data: Vec<String>,
+    hooks: Vec<String>,
+    default: Option<String>,
     ast: syn::DeriveInput,
 }
 
@@ -17,49 +167,63 @@ impl Args {
             _ => panic!("derive(Scope) is available for structs only"),
         };
 
-        let mut path = "/".to_owned();
-        for attr in ast.attrs.iter().flat_map(|attr| attr.parse_meta().ok()) {
-            if attr.name() == "path" {
-                match attr {
-                    syn::Meta::NameValue(meta) => match meta.lit {
-                        syn::Lit::Str(text) => {
-                            path = text.value()
-                        },
-                        _ => panic!("'path' attribute is invalid, should string value"),
-                    },
-                    _ => panic!("'path' attribute is invalid, should contain value"),
-                }
-            }
-        }
+        let container = Container::from_attrs(&ast.attrs);
 
         let mut service = Vec::new();
         let mut guards = Vec::new();
-        for field in struct_data.fields.iter() {
+        let mut data = Vec::new();
+        let mut hooks = Vec::new();
+        let mut default = None;
+        for (idx, field) in struct_data.fields.iter().enumerate() {
+            //Tuple struct's fields are accessed by index
+            let member = match field.ident {
+                Some(ref ident) => ident.to_string(),
+                None => idx.to_string(),
+            };
+
             for meta in field.attrs.iter().filter_map(|attr| attr.interpret_meta()) {
-                let variable_name = field.ident.as_ref().expect("Named field is needed");
-
-                if meta.name() == "service" {
-                    match meta {
-                        syn::Meta::Word(_) => {
-                            service.push(variable_name.to_string())
-                        },
-                        _ => panic!("'service' attribute for field '{}' is invalid. Should have no value", variable_name)
-                    }
-                } else if meta.name() == "guard" {
-                    match meta {
-                        syn::Meta::Word(_) => {
-                            guards.push(variable_name.to_string())
-                        },
-                        _ => panic!("'guard' attribute for field '{}' is invalid. Should have no value", variable_name)
-                    }
+                let attr = meta.name().to_string();
+
+                match attr.as_str() {
+                    "service" | "scope" | "guard" | "data" | "hook" | "default_resource" => match meta {
+                        syn::Meta::Word(_) => (),
+                        _ => panic!("'{}' attribute for field '{}' is invalid. Should have no value", attr, member)
+                    },
+                    _ => continue,
+                }
+
+                match attr.as_str() {
+                    "service" | "scope" => {
+                        let multiplicity = Multiplicity::from_type(&field.ty);
+                        service.push(Service {
+                            member: member.clone(),
+                            typ: multiplicity.service_type(&field.ty),
+                            multiplicity,
+                        })
+                    },
+                    "guard" => guards.push(member.clone()),
+                    "data" => data.push(member.clone()),
+                    "hook" => hooks.push(member.clone()),
+                    "default_resource" => match default {
+                        Some(_) => panic!("Second 'default_resource' field '{}' in scope! You cannot have more than one default resource", member),
+                        None => default = Some(member.clone()),
+                    },
+                    _ => unreachable!(),
                 }
             }
         }
 
+        if default.is_some() && container.default.is_some() {
+            panic!("Default resource is specified by both 'scope' attribute and field! You cannot have more than one default resource");
+        }
+
         Self {
-            path,
+            container,
             service,
             guards,
+            data,
+            hooks,
+            default,
             ast
         }
     }
@@ -76,21 +240,193 @@ impl Args {
 
 impl fmt::Display for Args {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = &self.ast.ident;
+        let param = service_param(&self.ast.generics);
         let (impl_gen, type_gen, where_clause) = self.ast.generics.split_for_impl();
 
-        writeln!(f, "{} {}{} {{", quote!(impl#impl_gen), self.ast.ident, quote!(#type_gen #where_clause))?;
+        let mut service_generics = self.ast.generics.clone();
+        service_generics.params.push(syn::parse_quote!(#param: 'static));
+        let (service_impl_gen, _, _) = service_generics.split_for_impl();
 
-        writeln!(f, "    pub fn actix_scope<P: 'static>(self) -> actix_web::Scope<P> {{")?;
+        let vis = &self.ast.vis;
+        let builder = format!("{}Builder", name);
+        let default_path = path_expr(&self.container.path, self.container.path_env.as_ref().map(|path_env| &path_env[..]));
 
-        write!(f, "        actix_web::Scope::new(\"{}\")", self.path)?;
+        //Registration falls back to derived scope, unless `#[scope] impl` provides
+        //inherent `actix_scope_register`, which takes priority over trait's method.
+        let fallback = format!("__{}ActixScopeRegister", name);
+        writeln!(f, "#[doc(hidden)]")?;
+        writeln!(f, "#[allow(non_camel_case_types)]")?;
+        writeln!(f, "trait {}<{}> {{", fallback, param)?;
+        writeln!(f, "    fn actix_scope_register(self, path: &str) -> actix_web::Scope<{}>;", param)?;
+        writeln!(f, "}}\n")?;
 
+        writeln!(f, "{} {}<{}> for {}{} {{", quote!(impl#service_impl_gen), fallback, param, name, quote!(#type_gen #where_clause))?;
+        writeln!(f, "    fn actix_scope_register(self, path: &str) -> actix_web::Scope<{}> {{", param)?;
+        writeln!(f, "        self.actix_scope_with_path(path)")?;
+        writeln!(f, "    }}\n}}\n")?;
+
+        //Same for routes, which are provided by `#[scope] impl`
+        let routes_fallback = format!("__{}ActixScopeRoutes", name);
+        writeln!(f, "#[doc(hidden)]")?;
+        writeln!(f, "#[allow(non_camel_case_types, dead_code)]")?;
+        writeln!(f, "trait {} {{", routes_fallback)?;
+        writeln!(f, "    fn actix_scope_routes(path: &str) -> {};", ROUTES_TYPE)?;
+        writeln!(f, "    fn actix_scope_openapi(path: &str) -> {};", OPERATIONS_TYPE)?;
+        writeln!(f, "}}\n")?;
+
+        writeln!(f, "{} {} for {}{} {{", quote!(impl#impl_gen), routes_fallback, name, quote!(#type_gen #where_clause))?;
+        writeln!(f, "    fn actix_scope_routes(_: &str) -> {} {{", ROUTES_TYPE)?;
+        writeln!(f, "        Vec::new()")?;
+        writeln!(f, "    }}\n")?;
+        writeln!(f, "    fn actix_scope_openapi(_: &str) -> {} {{", OPERATIONS_TYPE)?;
+        writeln!(f, "        Vec::new()")?;
+        writeln!(f, "    }}\n}}\n")?;
+
+        writeln!(f, "{} actix_web::dev::HttpServiceFactory<{}> for {}{} {{", quote!(impl#service_impl_gen), param, name, quote!(#type_gen #where_clause))?;
+        writeln!(f, "    fn register(self, config: &mut actix_web::dev::ServiceConfig<{}>) {{", param)?;
+        writeln!(f, "        actix_web::dev::HttpServiceFactory::register(self.builder().build(), config)")?;
+        writeln!(f, "    }}\n}}\n")?;
+
+        writeln!(f, "///Builder of [{}](struct.{}.html), which allows to configure scope at runtime.", name, name)?;
+        writeln!(f, "{} struct {}{} {{", quote!(#vis), builder, quote!(#impl_gen #where_clause))?;
+        writeln!(f, "    scope: {}{},", name, quote!(#type_gen))?;
+        writeln!(f, "    path: String,")?;
+        writeln!(f, "    guard: Option<actix_web::guard::AllGuard>,")?;
+        writeln!(f, "}}\n")?;
+
+        writeln!(f, "{} {}{} {{", quote!(impl#impl_gen), builder, quote!(#type_gen #where_clause))?;
+        writeln!(f, "    ///Overrides scope's path.")?;
+        writeln!(f, "    pub fn path(mut self, path: impl Into<String>) -> Self {{")?;
+        writeln!(f, "        self.path = path.into();")?;
+        writeln!(f, "        self")?;
+        writeln!(f, "    }}\n")?;
+        writeln!(f, "    ///Adds guard to the scope.")?;
+        writeln!(f, "    pub fn guard(mut self, guard: impl actix_web::guard::Guard + 'static) -> Self {{")?;
+        writeln!(f, "        self.guard = Some(match self.guard.take() {{")?;
+        writeln!(f, "            Some(all) => all.and(guard),")?;
+        writeln!(f, "            None => actix_web::guard::All(guard),")?;
+        writeln!(f, "        }});")?;
+        writeln!(f, "        self")?;
+        writeln!(f, "    }}\n")?;
+        writeln!(f, "    ///Returns routes of scope with configured path.")?;
+        writeln!(f, "    pub fn routes(&self) -> {} {{", ROUTES_TYPE)?;
+        writeln!(f, "        <{}{}>::routes_with_path(&self.path)", name, quote!(#type_gen))?;
+        writeln!(f, "    }}\n")?;
+        writeln!(f, "    ///Returns OpenAPI 3 document of scope with configured path.")?;
+        writeln!(f, "    pub fn openapi(&self) -> String {{")?;
+        writeln!(f, "        <{}{}>::openapi_with_path(&self.path)", name, quote!(#type_gen))?;
+        writeln!(f, "    }}\n")?;
+        writeln!(f, "    ///Creates scope, ready to be registered as service.")?;
+        writeln!(f, "    pub fn build<{}: 'static>(self) -> impl actix_web::dev::HttpServiceFactory<{}> {{", param, param)?;
+        writeln!(f, "        let scope: actix_web::Scope<{}> = self.scope.actix_scope_register(&self.path);", param)?;
+        write!(f, "        let scope = match self.guard {{ Some(guard) => scope.guard(guard), None => scope }};")?;
+        for wrap in self.container.wrap.iter() {
+            write!(f, "\n        let scope = scope.wrap({});", wrap)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "        scope")?;
+        writeln!(f, "    }}\n}}\n")?;
+
+        write_scope_info(f, &quote!(impl#impl_gen).to_string(), &format!("{}{}", name, quote!(#type_gen)), &quote!(#where_clause).to_string())?;
+
+        writeln!(f, "{} {}{} {{", quote!(impl#impl_gen), name, quote!(#type_gen #where_clause))?;
+
+        writeln!(f, "    ///Creates builder to configure scope at runtime.")?;
+        writeln!(f, "    pub fn builder(self) -> {}{} {{", builder, quote!(#type_gen))?;
+        writeln!(f, "        {} {{", builder)?;
+        writeln!(f, "            scope: self,")?;
+        writeln!(f, "            path: String::from({}),", default_path)?;
+        writeln!(f, "            guard: None,")?;
+        writeln!(f, "        }}")?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Returns routes of scope with full paths.")?;
+        writeln!(f, "    pub fn routes() -> {} {{", ROUTES_TYPE)?;
+        writeln!(f, "        Self::routes_with_path({})", default_path)?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Returns routes of scope, which is served at `path`.")?;
+        writeln!(f, "    #[allow(unused_mut)]")?;
+        writeln!(f, "    pub fn routes_with_path(path: &str) -> {} {{", ROUTES_TYPE)?;
+        write_routes_fallback(f)?;
+        writeln!(f, "        let mut routes = Self::actix_scope_routes(path);")?;
         for service in self.service.iter() {
-            write!(f, ".service(self.{})", service)?;
+            write_nested_routes(f, &service.typ)?;
+        }
+        writeln!(f, "        routes")?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Returns OpenAPI operations of scope with full paths.")?;
+        writeln!(f, "    pub fn openapi_operations() -> {} {{", OPERATIONS_TYPE)?;
+        writeln!(f, "        Self::openapi_operations_with_path({})", default_path)?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    ///Returns OpenAPI operations of scope, which is served at `path`.")?;
+        writeln!(f, "    #[allow(unused_mut)]")?;
+        writeln!(f, "    pub fn openapi_operations_with_path(path: &str) -> {} {{", OPERATIONS_TYPE)?;
+        write_routes_fallback(f)?;
+        writeln!(f, "        let mut operations = Self::actix_scope_openapi(path);")?;
+        for service in self.service.iter() {
+            write_nested_operations(f, &service.typ)?;
+        }
+        write_operations_tags(f, &self.container.tags)?;
+        writeln!(f, "        operations")?;
+        writeln!(f, "    }}\n")?;
+
+        openapi::write_document(f)?;
+        writeln!(f)?;
+        openapi::write_document_with_path(f)?;
+        writeln!(f)?;
+
+        writeln!(f, "    pub fn actix_scope<{}: 'static>(self) -> actix_web::Scope<{}> {{", param, param)?;
+        writeln!(f, "        self.actix_scope_with_path({})", default_path)?;
+        writeln!(f, "    }}\n")?;
+
+        writeln!(f, "    pub fn actix_scope_with_path<{}: 'static>(self, path: &str) -> actix_web::Scope<{}> {{", param, param)?;
+
+        if self.container.docs.is_some() {
+            writeln!(f, "        let docs = Self::openapi_with_path(path);")?;
+        }
+
+        write!(f, "        let scope = actix_web::Scope::new(path)")?;
+
+        for guard in self.container.guards.iter() {
+            write!(f, ".guard(actix_web::guard::fn_guard({}))", guard)?;
         }
         for guard in self.guards.iter() {
             write!(f, ".guard(self.{})", guard)?;
         }
+        for data in self.data.iter() {
+            write!(f, ".data(self.{})", data)?;
+        }
+
+        for hook in self.hooks.iter() {
+            write!(f, ";\n        let scope = (self.{})(scope)", hook)?;
+        }
+
+        write!(f, ";\n        let scope = scope")?;
+        if let Some(default) = self.container.default.as_ref() {
+            write!(f, ".default_resource({})", default)?;
+        }
+
+        for service in self.service.iter() {
+            match service.multiplicity {
+                Multiplicity::Single => write!(f, ".service(self.{})", service.member)?,
+                Multiplicity::Optional => write!(f, ";\n        let scope = match self.{} {{ Some(service) => scope.service(service), None => scope }}", service.member)?,
+                Multiplicity::Repeated => write!(f, ";\n        let scope = self.{}.into_iter().fold(scope, |scope, service| scope.service(service))", service.member)?,
+            }
+        }
+
+        if let Some(docs) = self.container.docs.as_ref() {
+            crate::docs::write_services(f, docs)?;
+        }
+
+        if let Some(default) = self.default.as_ref() {
+            write!(f, ".default_resource(self.{})", default)?;
+        }
 
+        write!(f, ";\n        scope")?;
         writeln!(f, "\n    }}")?;
         writeln!(f, "}}")
     }
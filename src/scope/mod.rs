@@ -1,2 +1,82 @@
 pub mod attr;
 pub mod derive;
+
+use std::fmt;
+
+use crate::route::ROUTES_TYPE;
+use crate::openapi::OPERATIONS_TYPE;
+
+///Picks name of `HttpServiceFactory` type parameter, that doesn't collide with user's generics.
+fn service_param(generics: &syn::Generics) -> syn::Ident {
+    let is_used = |name: &str| generics.type_params().any(|param| param.ident == name);
+
+    let name = match is_used("P") {
+        false => "P",
+        true => "__P",
+    };
+
+    syn::Ident::new(name, proc_macro2::Span::call_site())
+}
+
+///Writes trait, which provides empty `routes()` and `openapi_operations()` for services not generated by this crate.
+///
+///Inherent methods of generated services take priority over trait's ones.
+fn write_routes_fallback(f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "        #[allow(non_camel_case_types, dead_code)]")?;
+    writeln!(f, "        trait actix_scope_no_routes {{")?;
+    writeln!(f, "            fn routes() -> {} {{ Vec::new() }}", ROUTES_TYPE)?;
+    writeln!(f, "            fn openapi_operations() -> {} {{ Vec::new() }}", OPERATIONS_TYPE)?;
+    writeln!(f, "        }}")?;
+    writeln!(f, "        impl<T> actix_scope_no_routes for T {{}}")
+}
+
+///Writes impl of `ScopeInfo` for service `typ`, which delegates to its inherent `routes()`, if `runtime` feature is enabled.
+///
+///`impl_gen` is `impl` with generics of `typ` (e.g. `impl<T>`), which are bounded by `where_clause`.
+fn write_scope_info(f: &mut fmt::Formatter, impl_gen: &str, typ: &str, where_clause: &str) -> fmt::Result {
+    if !cfg!(feature = "runtime") {
+        return Ok(());
+    }
+
+    writeln!(f, "{} actix_web_cute_runtime::ScopeInfo for {} {} {{", impl_gen, typ, where_clause)?;
+    writeln!(f, "    fn routes() -> {} {{", ROUTES_TYPE)?;
+    writeln!(f, "        <{}>::routes()", typ)?;
+    writeln!(f, "    }}")?;
+    writeln!(f, "}}\n")
+}
+
+///Writes statement, which appends routes of service `typ` to `routes`, prefixing them with `path`.
+fn write_nested_routes(f: &mut fmt::Formatter, typ: &str) -> fmt::Result {
+    writeln!(f, "        for (route, methods, name) in <{}>::routes() {{", typ)?;
+    writeln!(f, "            routes.push((format!(\"{{}}{{}}\", path, route), methods, name));")?;
+    writeln!(f, "        }}")
+}
+
+///Writes statement, which appends OpenAPI operations of service `typ` to `operations`, prefixing them with `path`.
+fn write_nested_operations(f: &mut fmt::Formatter, typ: &str) -> fmt::Result {
+    writeln!(f, "        for (route, method, tags, parameters, fields, schemas) in <{}>::openapi_operations() {{", typ)?;
+    writeln!(f, "            operations.push((format!(\"{{}}{{}}\", path, route), method, tags, parameters, fields, schemas));")?;
+    writeln!(f, "        }}")
+}
+
+///Writes statement, which adds scope's `tags` to every operation.
+fn write_operations_tags(f: &mut fmt::Formatter, tags: &[String]) -> fmt::Result {
+    if !tags.is_empty() {
+        writeln!(f, "        for operation in operations.iter_mut() {{")?;
+        writeln!(f, "            operation.2.extend_from_slice(&{:?});", tags)?;
+        writeln!(f, "        }}")?;
+    }
+
+    Ok(())
+}
+
+///Creates expression of type `&str` with scope's path.
+///
+///If `path_env` is specified, path is read from environment variable on registration,
+///falling back to `path`.
+fn path_expr(path: &str, path_env: Option<&str>) -> String {
+    match path_env {
+        Some(path_env) => format!("&std::env::var({:?}).unwrap_or_else(|_| String::from({:?}))", path_env, path),
+        None => format!("{:?}", path),
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+
+use crate::route::ROUTES_TYPE;
+
+///Code of `smoke_test_with()`, which requests every route of `routes` on `srv`.
+///
+///Path parameters are filled with `1`. Every request carries token, which is recorded by marker guard
+///of route that is reached (see `write_marked_route`), so that request must reach route of the same name.
+const SMOKE_TEST: &str = r#"
+        let sample = |path: &str| {
+            let mut sample = String::new();
+            let mut rest = path;
+            while let Some(start) = rest.find('{') {
+                let end = match rest[start..].find('}') {
+                    Some(end) => start + end,
+                    None => break,
+                };
+                sample.push_str(&rest[..start]);
+                sample.push('1');
+                rest = &rest[end+1..];
+            }
+            sample.push_str(rest);
+            sample
+        };
+
+        for (route, methods, name) in routes {
+            let uri = sample(&route);
+            let methods = match methods.is_empty() {
+                true => &["GET"][..],
+                false => methods,
+            };
+
+            for method in methods {
+                let method = actix_web::http::Method::from_bytes(method.as_bytes()).expect("Parse method of route");
+                let token = actix_web_cute_runtime::smoke::token();
+                let request = prepare(srv.request(method.clone(), srv.url(&uri))).header(actix_web_cute_runtime::smoke::HEADER, token.as_str());
+                let status = srv.block_on(request.send()).expect("Send request to route").status();
+
+                match actix_web_cute_runtime::smoke::take(&token) {
+                    Some(reached) => assert!(reached == name, "{} {} of '{}' reaches '{}', got {}", method, uri, name, reached, status),
+                    None => panic!("{} {} of '{}' reaches no route, got {}", method, uri, name, status),
+                }
+            }
+        }
+"#;
+
+///Writes `#[cfg(test)]` impl with `smoke_test()` and `smoke_test_with()` of `service`.
+///
+///Routes to request are appended to `routes` by statements of `write_routes`, as only routes
+///with marker guard (i.e. scope's own routes) can be checked.
+///Requests are sent by `actix_http_test::TestServer`, which is expected to be available within tests.
+pub fn write_smoke_test(f: &mut fmt::Formatter, service: &str, write_routes: &dyn Fn(&mut fmt::Formatter) -> fmt::Result) -> fmt::Result {
+    writeln!(f, "#[cfg(test)]")?;
+    writeln!(f, "#[allow(dead_code)]")?;
+    writeln!(f, "impl {} {{", service)?;
+    writeln!(f, "    ///Sends request per method and path of every route, asserting that it reaches handler.")?;
+    writeln!(f, "    pub fn smoke_test() {{")?;
+    writeln!(f, "        Self::smoke_test_with(|request| request)")?;
+    writeln!(f, "    }}\n")?;
+    writeln!(f, "    ///Same as `smoke_test()`, but every request is modified by `prepare` (e.g. to pass guards).")?;
+    writeln!(f, "    pub fn smoke_test_with<F: Fn(awc::ClientRequest) -> awc::ClientRequest>(prepare: F) {{")?;
+    writeln!(f, "        let mut srv = actix_http_test::TestServer::new(|| actix_http::HttpService::new(actix_web::App::new().service({})));", service)?;
+    writeln!(f, "        let mut routes: {} = Vec::new();", ROUTES_TYPE)?;
+    write_routes(f)?;
+    f.write_str(SMOKE_TEST)?;
+    writeln!(f, "    }}")?;
+    writeln!(f, "}}")
+}
+
+///Writes expression, which is `route` with marker guard, that records `name` for requests of `smoke_test()`.
+///
+///Marker is registered only within tests of scope with `smoke_test`, as the last guard,
+///so that it is checked once route is matched otherwise.
+pub fn write_marked_route(f: &mut fmt::Formatter, route: &str, name: &str) -> fmt::Result {
+    write!(f, "{{ let route = {};", route)?;
+    write!(f, " #[cfg(test)] let route = route.guard(actix_web::guard::fn_guard(|head: &actix_web::dev::RequestHead| {{")?;
+    write!(f, " actix_web_cute_runtime::smoke::record(head.headers.get(actix_web_cute_runtime::smoke::HEADER).and_then(|token| token.to_str().ok()), \"{}\")", name)?;
+    write!(f, " }})); route }}")
+}
@@ -0,0 +1,418 @@
+use std::{env, fs};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::openapi::{extractor, type_args};
+use crate::manifest::{self, Location};
+use crate::route::{path_parameters, GuardType};
+
+///Environment variable with directory to write TypeScript modules into.
+const TS_DIR: &str = "CUTE_CODEGEN_TS_DIR";
+
+///Returns directory to write modules into, if `typescript` feature is enabled and directory is configured.
+fn directory() -> Option<PathBuf> {
+    match cfg!(feature = "typescript") {
+        true => env::var_os(TS_DIR).map(PathBuf::from),
+        false => None,
+    }
+}
+
+///Writes TypeScript module `<file>.ts` of item `name`, if it is enabled.
+///
+///Fails, if other item has written the same module.
+fn write(file: &str, name: &str, text: &str) {
+    let dir = match directory() {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    if let Err(error) = fs::create_dir_all(&dir) {
+        panic!("Unable to create directory '{}' for TypeScript: {}", dir.display(), error);
+    }
+
+    let path = dir.join(format!("{}.ts", file));
+    manifest::claim(&path, &Location::call_site(), name);
+
+    if let Err(error) = fs::write(&path, text) {
+        panic!("Unable to write TypeScript module '{}': {}", path.display(), error);
+    }
+}
+
+///Converts Rust type into TypeScript, collecting names of interfaces it refers to into `imports`.
+///
+///Types, which are not known, are expected to be exported by `#[derive(TypeScript)]`.
+fn ts_type(typ: &syn::Type, imports: &mut Vec<String>) -> String {
+    let element = |typ: &syn::Type, imports: &mut Vec<String>| match ts_type(typ, imports) {
+        ref text if text.contains(' ') => format!("({})[]", text),
+        text => format!("{}[]", text),
+    };
+
+    match typ {
+        syn::Type::Reference(typ) => ts_type(&typ.elem, imports),
+        syn::Type::Paren(typ) => ts_type(&typ.elem, imports),
+        syn::Type::Group(typ) => ts_type(&typ.elem, imports),
+        syn::Type::Slice(typ) => element(&typ.elem, imports),
+        syn::Type::Array(typ) => element(&typ.elem, imports),
+        syn::Type::Tuple(typ) if typ.elems.is_empty() => "null".to_owned(),
+        syn::Type::Tuple(typ) => format!("[{}]", typ.elems.iter().map(|typ| ts_type(typ, imports)).collect::<Vec<_>>().join(", ")),
+        syn::Type::Path(typ) => {
+            let segment = match typ.path.segments.iter().last() {
+                Some(segment) => segment,
+                None => return "any".to_owned(),
+            };
+            let args = type_args(segment);
+
+            match (segment.ident.to_string().as_str(), args.as_slice()) {
+                ("u8", _) | ("u16", _) | ("u32", _) | ("u64", _) | ("usize", _) |
+                ("i8", _) | ("i16", _) | ("i32", _) | ("i64", _) | ("isize", _) |
+                ("f32", _) | ("f64", _) => "number".to_owned(),
+                ("String", _) | ("str", _) | ("char", _) => "string".to_owned(),
+                ("bool", _) => "boolean".to_owned(),
+                ("Vec", [typ]) | ("VecDeque", [typ]) | ("HashSet", [typ]) | ("BTreeSet", [typ]) => element(typ, imports),
+                ("Option", [typ]) => format!("{} | null", ts_type(typ, imports)),
+                ("HashMap", [_, typ]) | ("BTreeMap", [_, typ]) => format!("{{ [key: string]: {} }}", ts_type(typ, imports)),
+                ("Box", [typ]) | ("Rc", [typ]) | ("Arc", [typ]) | ("Cow", [typ]) => ts_type(typ, imports),
+                ("Json", [typ]) | ("Form", [typ]) | ("Query", [typ]) | ("Path", [typ]) => ts_type(typ, imports),
+                (name, args) => {
+                    if !imports.iter().any(|import| import == name) {
+                        imports.push(name.to_owned());
+                    }
+                    match args.is_empty() {
+                        true => name.to_owned(),
+                        false => format!("{}<{}>", name, args.iter().map(|typ| ts_type(typ, imports)).collect::<Vec<_>>().join(", ")),
+                    }
+                },
+            }
+        },
+        _ => "any".to_owned(),
+    }
+}
+
+///Returns name of TypeScript parameter for path parameter, falling back to its position.
+fn parameter_name(name: &str, idx: usize) -> String {
+    let is_ident = !name.is_empty() && !name.starts_with(|ch: char| ch.is_ascii_digit()) && name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    match is_ident {
+        true => name.to_owned(),
+        false => format!("param{}", idx),
+    }
+}
+
+///Parses type of extractor, returned by `extractor`.
+fn parse_type(text: &str) -> syn::Type {
+    syn::parse_str(text).expect("Parse type of extractor")
+}
+
+///Typed `fetch` wrapper of route.
+#[derive(Clone)]
+pub struct Function {
+    name: String,
+    method: &'static str,
+    path: String,
+    ///Names and types of path parameters.
+    parameters: Vec<(String, String)>,
+    query: Option<String>,
+    ///Type of body and whether it is sent as JSON, instead of form.
+    body: Option<(String, bool)>,
+    ///Type of JSON response.
+    response: Option<String>,
+    imports: Vec<String>,
+}
+
+impl Function {
+    ///Creates wrapper of route's handler, unless route accepts any method.
+    pub fn new(fun: &syn::ItemFn, path: &str, guard: &GuardType) -> Option<Self> {
+        let method = guard.method()?;
+        let names = path_parameters(path);
+
+        let mut imports = Vec::new();
+        let mut types = Vec::new();
+        let mut query = None;
+        let mut body = None;
+        for arg in fun.decl.inputs.iter() {
+            let typ = match arg {
+                syn::FnArg::Captured(arg) => &arg.ty,
+                _ => continue,
+            };
+
+            match extractor(typ) {
+                Some((ref name, ref inner)) if name == "Path" => types = match parse_type(inner) {
+                    syn::Type::Tuple(ref tuple) if tuple.elems.len() == names.len() => tuple.elems.iter().map(|typ| ts_type(typ, &mut imports)).collect(),
+                    ref typ if names.len() == 1 => vec![ts_type(typ, &mut imports)],
+                    _ => Vec::new(),
+                },
+                Some((ref name, ref inner)) if name == "Query" => query = Some(ts_type(&parse_type(inner), &mut imports)),
+                Some((ref name, ref inner)) if name == "Json" || name == "Form" => body = Some((ts_type(&parse_type(inner), &mut imports), name == "Json")),
+                _ => continue,
+            }
+        }
+
+        let parameters = names.iter().enumerate().map(|(idx, name)| {
+            let typ = types.get(idx).cloned().unwrap_or_else(|| "string | number".to_owned());
+            (parameter_name(name, idx), typ)
+        }).collect();
+
+        let response = match fun.decl.output {
+            syn::ReturnType::Type(_, ref typ) => match extractor(typ) {
+                Some((ref name, ref inner)) if name == "Json" => Some(ts_type(&parse_type(inner), &mut imports)),
+                _ => None,
+            },
+            syn::ReturnType::Default => None,
+        };
+
+        Some(Self {
+            name: fun.ident.to_string(),
+            method,
+            path: path.to_owned(),
+            parameters,
+            query,
+            body,
+            response,
+            imports,
+        })
+    }
+
+    ///Writes function `<name_prefix><name>`, which requests route at `<base_url><path_prefix><path>`.
+    ///
+    ///JSON responses are decoded, while others are returned as text.
+    ///Parameters of `path_prefix` are not typed, as scope has no extractor for them.
+    fn write(&self, out: &mut String, name_prefix: &str, path_prefix: &str) -> std::fmt::Result {
+        let mut parameters = path_parameters(path_prefix).iter().enumerate().map(|(idx, name)| {
+            (format!("scope_{}", parameter_name(name, idx)), "string | number".to_owned())
+        }).collect::<Vec<_>>();
+        parameters.extend(self.parameters.iter().cloned());
+
+        write!(out, "export async function {}{}(baseUrl: string", name_prefix, self.name)?;
+        for (name, typ) in parameters.iter() {
+            write!(out, ", {}: {}", name, typ)?;
+        }
+        if let Some(ref query) = self.query {
+            write!(out, ", query: {}", query)?;
+        }
+        if let Some((ref body, _)) = self.body {
+            write!(out, ", body: {}", body)?;
+        }
+        writeln!(out, "): Promise<{}> {{", self.response.as_deref().unwrap_or("string"))?;
+
+        let mut url = String::new();
+        let mut rest = format!("{}{}", path_prefix, self.path);
+        let mut parameters = parameters.iter();
+        while let Some(start) = rest.find('{') {
+            let (end, (name, _)) = match (rest[start..].find('}'), parameters.next()) {
+                (Some(end), Some(parameter)) => (start + end, parameter),
+                _ => break,
+            };
+            write!(url, "{}${{encodeURIComponent(String({}))}}", &rest[..start], name)?;
+            rest = rest[end+1..].to_owned();
+        }
+        url.push_str(&rest);
+
+        write!(out, "    const response = await fetch(baseUrl + `{}`", url)?;
+        if self.query.is_some() {
+            write!(out, " + \"?\" + new URLSearchParams(query as any)")?;
+        }
+        writeln!(out, ", {{")?;
+        writeln!(out, "        method: \"{}\",", self.method)?;
+        match self.body {
+            Some((_, true)) => {
+                writeln!(out, "        headers: {{ \"Content-Type\": \"application/json\" }},")?;
+                writeln!(out, "        body: JSON.stringify(body),")?;
+            },
+            Some((_, false)) => writeln!(out, "        body: new URLSearchParams(body as any),")?,
+            None => (),
+        }
+        writeln!(out, "    }});")?;
+        writeln!(out, "    if (!response.ok) {{")?;
+        writeln!(out, "        throw new Error(`${{response.status}} ${{response.statusText}}`);")?;
+        writeln!(out, "    }}")?;
+        match self.response {
+            Some(_) => writeln!(out, "    return await response.json();")?,
+            None => writeln!(out, "    return await response.text();")?,
+        }
+        writeln!(out, "}}\n")
+    }
+}
+
+///Writes module `name` with `functions`, each of them given with its name and path prefixes.
+pub fn write_module(name: &str, functions: &[(String, String, Function)]) {
+    if directory().is_none() {
+        return;
+    }
+
+    let mut imports = functions.iter().flat_map(|(_, _, function)| function.imports.iter()).collect::<Vec<_>>();
+    imports.sort();
+    imports.dedup();
+
+    let mut out = String::new();
+    out.push_str("// Generated by actix-web-cute-codegen. Do not edit.\n");
+    for import in imports {
+        out.push_str(&format!("import {{ {} }} from \"./{}\";\n", import, import));
+    }
+    out.push('\n');
+
+    for (name_prefix, path_prefix, function) in functions {
+        function.write(&mut out, name_prefix, path_prefix).expect("Write TypeScript function");
+    }
+
+    write(&Location::call_site().routes_file(name), name, &out);
+}
+
+///Returns `serde` options of item, which are either words or string values.
+fn serde_options(attrs: &[syn::Attribute]) -> Vec<(String, Option<String>)> {
+    let mut options = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("serde")) {
+        let list = match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list,
+            _ => continue,
+        };
+
+        for meta in list.nested.iter() {
+            match meta {
+                syn::NestedMeta::Meta(syn::Meta::Word(ident)) => options.push((ident.to_string(), None)),
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref meta)) => match meta.lit {
+                    syn::Lit::Str(ref text) => options.push((meta.ident.to_string(), Some(text.value()))),
+                    _ => options.push((meta.ident.to_string(), None)),
+                },
+                syn::NestedMeta::Meta(syn::Meta::List(ref list)) => options.push((list.ident.to_string(), None)),
+                syn::NestedMeta::Literal(_) => continue,
+            }
+        }
+    }
+
+    options
+}
+
+///Renames field (`snake_case`) or variant (`PascalCase`) as serde's `rename_all` does.
+fn rename(name: &str, rule: &str, is_variant: bool) -> String {
+    //Split into lowercase words, as both cases are converted via `snake_case`
+    let snake = match is_variant {
+        true => {
+            let mut snake = String::new();
+            for (idx, ch) in name.char_indices() {
+                if idx > 0 && ch.is_uppercase() {
+                    snake.push('_');
+                }
+                snake.push(ch.to_ascii_lowercase());
+            }
+            snake
+        },
+        false => name.to_owned(),
+    };
+    let pascal = snake.split('_').map(|word| {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+            None => String::new(),
+        }
+    }).collect::<String>();
+
+    match rule {
+        "lowercase" if is_variant => name.to_ascii_lowercase(),
+        "UPPERCASE" if is_variant => name.to_ascii_uppercase(),
+        "lowercase" => name.to_owned(),
+        "UPPERCASE" => name.to_ascii_uppercase(),
+        "PascalCase" => pascal,
+        "camelCase" => {
+            let mut chars = pascal.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        },
+        "snake_case" => snake,
+        "SCREAMING_SNAKE_CASE" => snake.to_ascii_uppercase(),
+        "kebab-case" => snake.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake.replace('_', "-").to_ascii_uppercase(),
+        rule => panic!("TypeScript definition doesn't support serde's rename_all = \"{}\"", rule),
+    }
+}
+
+///Returns serialized name of field or variant, unless it is skipped.
+///
+///Takes serde's `rename`, `skip` and container's `rename_all` into account.
+fn serde_name(ident: &syn::Ident, attrs: &[syn::Attribute], rename_all: Option<&str>, is_variant: bool) -> Option<String> {
+    let mut name = match rename_all {
+        Some(rule) => rename(&ident.to_string(), rule, is_variant),
+        None => ident.to_string(),
+    };
+
+    for (option, value) in serde_options(attrs) {
+        match (option.as_str(), value) {
+            ("skip", _) | ("skip_serializing", _) => return None,
+            ("rename", Some(value)) => name = value,
+            ("rename", None) => panic!("TypeScript definition doesn't support serde's rename of '{}' with separate names", ident),
+            ("flatten", _) => panic!("TypeScript definition doesn't support serde's flatten of '{}'", ident),
+            _ => continue,
+        }
+    }
+
+    Some(name)
+}
+
+///Writes module `<Name>.ts` with definition of type.
+///
+///Structs with named fields become interfaces, newtypes become aliases and
+///enums with unit variants become unions of strings. Serde's `rename`, `rename_all` and `skip`
+///are honored, while attributes, which change shape of type, are rejected.
+///
+///Type is validated even when output is disabled, so unsupported type fails regardless of configuration.
+pub fn write_definition(ast: &syn::DeriveInput) {
+    let mut rename_all = None;
+    for (option, value) in serde_options(&ast.attrs) {
+        match (option.as_str(), value) {
+            ("rename_all", Some(value)) => rename_all = Some(value),
+            ("rename_all", None) => panic!("TypeScript definition doesn't support serde's rename_all with separate rules"),
+            ("tag", _) | ("content", _) | ("untagged", _) | ("transparent", _) | ("into", _) | ("from", _) | ("try_from", _) | ("remote", _) => {
+                panic!("TypeScript definition doesn't support serde's '{}' of type '{}'", option, ast.ident)
+            },
+            _ => continue,
+        }
+    }
+    let rename_all = rename_all.as_deref();
+
+    let name = ast.ident.to_string();
+    let params = ast.generics.type_params().map(|param| param.ident.to_string()).collect::<Vec<_>>();
+    let generics = match params.is_empty() {
+        true => String::new(),
+        false => format!("<{}>", params.join(", ")),
+    };
+
+    let mut imports = Vec::new();
+    let definition = match ast.data {
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(ref fields), .. }) => {
+            let mut definition = format!("export interface {}{} {{\n", name, generics);
+            for field in fields.named.iter() {
+                let ident = field.ident.as_ref().expect("Named field to have name");
+                if let Some(field_name) = serde_name(ident, &field.attrs, rename_all, false) {
+                    definition.push_str(&format!("    {}: {};\n", field_name, ts_type(&field.ty, &mut imports)));
+                }
+            }
+            definition.push_str("}\n");
+            definition
+        },
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Unnamed(ref fields), .. }) if fields.unnamed.len() == 1 => {
+            let field = fields.unnamed.iter().next().expect("To have single field");
+            format!("export type {}{} = {};\n", name, generics, ts_type(&field.ty, &mut imports))
+        },
+        syn::Data::Enum(ref data) if data.variants.iter().all(|variant| matches!(variant.fields, syn::Fields::Unit)) => {
+            let variants = data.variants.iter().filter_map(|variant| serde_name(&variant.ident, &variant.attrs, rename_all, true))
+                                                .map(|variant| format!("\"{}\"", variant))
+                                                .collect::<Vec<_>>();
+            match variants.is_empty() {
+                true => format!("export type {} = never;\n", name),
+                false => format!("export type {} = {};\n", name, variants.join(" | ")),
+            }
+        },
+        _ => panic!("TypeScript definition is supported only for structs with named fields, newtypes and enums with unit variants"),
+    };
+
+    let mut out = String::new();
+    out.push_str("// Generated by actix-web-cute-codegen. Do not edit.\n");
+    for import in imports.iter().filter(|import| **import != name && !params.contains(import)) {
+        out.push_str(&format!("import {{ {} }} from \"./{}\";\n", import, import));
+    }
+    out.push('\n');
+    out.push_str(&definition);
+
+    write(&name, &name, &out);
+}
@@ -4,24 +4,40 @@ use actix_http::HttpService;
 use actix_web::{http, App, HttpResponse, Responder};
 use futures::{Future, future};
 
+use std::marker::PhantomData;
 use std::sync::atomic::{self, AtomicBool};
 
 static INIT: AtomicBool = AtomicBool::new(false);
 static HOOK_INIT: AtomicBool = AtomicBool::new(false);
 static NOT_USED_HOOK_INIT: AtomicBool = AtomicBool::new(false);
+static INSTANCE_HOOK_INIT: AtomicBool = AtomicBool::new(false);
 
 #[get("/test")]
 fn test() -> impl Responder {
     HttpResponse::Ok()
 }
 
+#[scope("/nested")]
+mod nested {
+    use super::*;
+
+    #[get("/test")]
+    pub fn test() -> impl Responder {
+        HttpResponse::Ok()
+    }
+}
+
 #[derive(Scope)]
-#[path="/my_scope"]
+#[scope(path="/my_scope", tag="my_scope")]
 pub struct MyScope {
     #[service]
     test: test,
+    #[scope]
+    nested: nested::scope,
     #[guard]
     guard: actix_web::guard::AnyGuard,
+    #[data]
+    config: usize,
 }
 
 #[scope]
@@ -29,11 +45,13 @@ impl MyScope {
     fn new() -> Self {
         Self {
             test: test,
+            nested: nested::scope,
             guard: actix_web::guard::Any(actix_web::guard::Get()).or(actix_web::guard::Post()),
+            config: 42,
         }
     }
 
-    //Special member to act as hook
+    #[hook]
     pub fn init<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
         INIT.store(true, atomic::Ordering::Relaxed);
         scope
@@ -51,6 +69,7 @@ impl MyScope {
     //    true
     //}
 
+    #[allow(dead_code)]
     pub fn init_scope_unused<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
         NOT_USED_HOOK_INIT.store(true, atomic::Ordering::Relaxed);
         scope
@@ -61,11 +80,245 @@ impl MyScope {
         future::ok(HttpResponse::Ok().finish())
     }
 
+    #[get("/test_data")]
+    pub fn test_data(config: actix_web::web::Data<usize>) -> impl Responder {
+        match *config.get_ref() {
+            42 => HttpResponse::Ok(),
+            _ => HttpResponse::InternalServerError(),
+        }
+    }
+
+    #[default_resource]
     pub fn default_resource<P: 'static>(res: actix_web::Resource<P>) -> actix_web::Resource<P> {
         res.to(|| HttpResponse::InternalServerError())
     }
 }
 
+pub trait Greeting: 'static {
+    fn greet() -> &'static str;
+}
+
+pub struct Hello;
+
+impl Greeting for Hello {
+    fn greet() -> &'static str {
+        "Hello"
+    }
+}
+
+#[derive(Scope)]
+#[scope(path="/generic")]
+pub struct GenericScope<G: Greeting> {
+    greeting: PhantomData<G>,
+}
+
+#[scope]
+impl<G> self::GenericScope<G> where G: Greeting {
+    fn new() -> Self {
+        Self {
+            greeting: PhantomData,
+        }
+    }
+
+    #[get("/greet")]
+    pub fn greet() -> impl Responder {
+        HttpResponse::Ok().body(G::greet())
+    }
+}
+
+#[derive(Scope)]
+#[scope(path="/standalone")]
+pub struct StandaloneScope {
+    #[service]
+    test: test,
+}
+
+pub fn standalone_default<P: 'static>(res: actix_web::Resource<P>) -> actix_web::Resource<P> {
+    res.to(|| HttpResponse::NotImplemented())
+}
+
+#[derive(Scope)]
+#[scope(path="/configured", default="standalone_default", wrap="actix_web::middleware::DefaultHeaders::new().header(\"X-Scope\", \"configured\")")]
+pub struct ConfiguredScope {
+    #[service]
+    test: test,
+}
+
+#[test]
+fn test_scope_openapi() {
+    let document = MyScope::openapi();
+
+    assert!(document.contains("\"/my_scope/test_async\":{\"get\":{\"tags\":[\"my_scope\"]"));
+    assert!(document.contains("\"/my_scope/nested/test\":{\"get\":{\"tags\":[\"my_scope\"]"));
+}
+
+#[test]
+fn test_scope_routes() {
+    let routes = MyScope::routes();
+    let routes = routes.iter().map(|(path, methods, name)| (path.as_str(), *methods, *name)).collect::<Vec<_>>();
+
+    assert_eq!(routes, vec![
+        ("/my_scope/test_async", &["GET"][..], "test_async"),
+        ("/my_scope/test_data", &["GET"][..], "test_data"),
+        ("/my_scope/test", &["GET"][..], "test"),
+        ("/my_scope/nested/test", &["GET"][..], "test"),
+    ]);
+
+    #[cfg(feature = "runtime")]
+    assert_eq!(<MyScope as actix_web_cute_runtime::ScopeInfo>::routes(), MyScope::routes());
+}
+
+#[test]
+fn test_configured_scope() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(ConfiguredScope { test })));
+
+    let request = srv.request(http::Method::GET, srv.url("/configured/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(response.headers().get("X-Scope").unwrap(), "configured");
+
+    let request = srv.request(http::Method::GET, srv.url("/configured/unknown"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::NOT_IMPLEMENTED);
+}
+
+#[get("/first")]
+fn first() -> impl Responder {
+    HttpResponse::Ok()
+}
+
+#[get("/second")]
+fn second() -> impl Responder {
+    HttpResponse::Ok()
+}
+
+pub struct Named(&'static str);
+
+impl<P: 'static> actix_web::dev::HttpServiceFactory<P> for Named {
+    fn register(self, config: &mut actix_web::dev::ServiceConfig<P>) {
+        let resource = actix_web::Resource::new(self.0).to(|| HttpResponse::Ok());
+        actix_web::dev::HttpServiceFactory::register(resource, config)
+    }
+}
+
+#[derive(Scope)]
+#[scope(path="/runtime")]
+pub struct RuntimeScope(#[service] Option<first>, #[service] Option<second>, #[service] Vec<Named>);
+
+#[test]
+fn test_runtime_scope() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(RuntimeScope(Some(first), None, vec![Named("/a"), Named("/b")]))));
+
+    let request = srv.request(http::Method::GET, srv.url("/runtime/first"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    let request = srv.request(http::Method::GET, srv.url("/runtime/second"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_client_error());
+
+    let request = srv.request(http::Method::GET, srv.url("/runtime/a"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    let request = srv.request(http::Method::GET, srv.url("/runtime/b"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+}
+
+#[test]
+fn test_standalone_scope() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(StandaloneScope { test })));
+
+    let request = srv.request(http::Method::GET, srv.url("/standalone/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+}
+
+#[test]
+fn test_scope_builder() {
+    let mut srv = TestServer::new(|| {
+        let scope = StandaloneScope { test }.builder().path("/overridden").guard(actix_web::guard::Get()).build();
+        HttpService::new(App::new().service(scope))
+    });
+
+    let request = srv.request(http::Method::GET, srv.url("/overridden/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    let request = srv.request(http::Method::GET, srv.url("/standalone/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_client_error());
+
+    let builder = StandaloneScope { test }.builder().path("/overridden");
+    assert_eq!(builder.routes(), vec![(String::from("/overridden/test"), &["GET"][..], "test")]);
+    assert!(builder.openapi().contains("\"/overridden/test\":{\"get\""));
+}
+
+#[derive(Scope)]
+#[scope(path="/env_default", path_env="CUTE_CODEGEN_ENV_SCOPE")]
+pub struct EnvScope {
+    #[service]
+    test: test,
+}
+
+#[test]
+fn test_env_scope() {
+    std::env::set_var("CUTE_CODEGEN_ENV_SCOPE", "/from_env");
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(EnvScope { test })));
+
+    let request = srv.request(http::Method::GET, srv.url("/from_env/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    let request = srv.request(http::Method::GET, srv.url("/env_default/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_client_error());
+}
+
+#[test]
+fn test_generic_scope() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(GenericScope::<Hello>::new())));
+
+    let request = srv.request(http::Method::GET, srv.url("/generic/greet"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+}
+
+#[derive(Clone, Scope)]
+#[scope(path="/instance")]
+pub struct InstanceScope {
+    greeting: &'static str,
+}
+
+#[scope]
+impl InstanceScope {
+    #[hook]
+    pub fn init_instance<P: 'static>(&self, scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
+        INSTANCE_HOOK_INIT.store(self.greeting == "Hello", atomic::Ordering::Relaxed);
+        scope
+    }
+
+    #[get("/greet/{name}")]
+    pub fn greet(&self, name: actix_web::web::Path<String>) -> impl Responder {
+        match name.as_str() {
+            "world" if self.greeting == "Hello" => HttpResponse::Ok(),
+            _ => HttpResponse::InternalServerError(),
+        }
+    }
+}
+
+#[test]
+fn test_instance_scope() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(InstanceScope { greeting: "Hello" })));
+
+    let request = srv.request(http::Method::GET, srv.url("/instance/greet/world"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    assert!(INSTANCE_HOOK_INIT.load(atomic::Ordering::Relaxed));
+}
+
 #[test]
 fn test_my_scope() {
     let mut srv = TestServer::new(|| HttpService::new(App::new().service(MyScope::new())));
@@ -78,6 +331,14 @@ fn test_my_scope() {
     let response = srv.block_on(request.send()).unwrap();
     assert!(response.status().is_success());
 
+    let request = srv.request(http::Method::GET, srv.url("/my_scope/nested/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    let request = srv.request(http::Method::GET, srv.url("/my_scope/test_data"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
     let request = srv.request(http::Method::GET, srv.url("/unknown"));
     let response = srv.block_on(request.send()).unwrap();
     assert!(response.status().is_client_error());
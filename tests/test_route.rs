@@ -1,7 +1,7 @@
 use actix_http::HttpService;
 use actix_http_test::TestServer;
-use actix_web_cute_codegen::get;
-use actix_web::{http, App, HttpResponse, Responder};
+use actix_web_cute_codegen::{get, post};
+use actix_web::{http, web, App, HttpResponse, Responder};
 use futures::{Future, future};
 
 //fn guard_head(head: &actix_web::dev::RequestHead) -> bool {
@@ -29,6 +29,31 @@ fn auto_sync() -> impl Future<Item=HttpResponse, Error=actix_web::Error> {
     future::ok(HttpResponse::Ok().finish())
 }
 
+///Handler that responds to HEAD.
+#[get("/test", head)]
+fn head_test() -> impl Responder {
+    HttpResponse::Ok().body("body")
+}
+
+#[get("/test", data="42usize")]
+fn data_test(data: web::Data<usize>) -> impl Responder {
+    match *data.get_ref() {
+        42 => HttpResponse::Ok(),
+        _ => HttpResponse::InternalServerError(),
+    }
+}
+
+fn resource_hook<P: 'static>(resource: actix_web::Resource<P>) -> actix_web::Resource<P> {
+    resource.data(42usize)
+}
+
+#[get("/test", hook="resource_hook")]
+fn hook_test(data: web::Data<usize>) -> impl Responder {
+    match *data.get_ref() {
+        42 => HttpResponse::Ok(),
+        _ => HttpResponse::InternalServerError(),
+    }
+}
 
 #[test]
 fn test_body() {
@@ -60,3 +85,110 @@ fn test_auto_async() {
     let response = srv.block_on(request.send()).unwrap();
     assert!(response.status().is_success());
 }
+
+#[test]
+fn test_data() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(data_test)));
+
+    let request = srv.request(http::Method::GET, srv.url("/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+}
+
+///Returns item.
+///
+///Item is looked up by id.
+#[get("/items/{id}", tag="items")]
+fn item_test(_id: web::Path<u32>, _query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    HttpResponse::Ok()
+}
+
+#[post("/items")]
+fn create_item_test(_names: web::Json<Vec<Option<String>>>) -> impl Responder {
+    HttpResponse::Created()
+}
+
+#[get("/counts", tag="counts \"all\"")]
+fn counts_test() -> web::Json<Vec<u32>> {
+    web::Json(vec![1, 2])
+}
+
+#[get("/greeting")]
+fn greeting_test() -> String {
+    "hello".to_owned()
+}
+
+#[test]
+fn test_openapi() {
+    let document = item_test::openapi();
+
+    assert!(document.starts_with("{\"openapi\":\"3.0.0\""));
+    assert!(document.contains("\"/items/{id}\":{\"get\":{\"tags\":[\"items\"]"));
+    assert!(document.contains("{\"name\":\"id\",\"in\":\"path\",\"required\":true,\"schema\":{\"type\":\"integer\"}}"));
+    assert!(document.contains("\"in\":\"query\",\"style\":\"form\",\"explode\":true,\"schema\":{\"type\":\"object\",\"additionalProperties\":{\"type\":\"string\"}}"));
+    assert!(document.contains("\"summary\":\"Returns item.\",\"description\":\"Item is looked up by id.\""));
+
+    let document = create_item_test::openapi();
+    assert!(document.contains("\"schema\":{\"type\":\"array\",\"items\":{\"type\":\"string\",\"nullable\":true}}"));
+
+    let document = counts_test::openapi();
+    assert!(document.contains("\"tags\":[\"counts \\\"all\\\"\"]"));
+    assert!(document.contains("\"content\":{\"application/json\":{\"schema\":{\"type\":\"array\",\"items\":{\"type\":\"integer\"}}}}"));
+
+    let document = greeting_test::openapi();
+    assert!(document.contains("\"content\":{\"text/plain\":{\"schema\":{\"type\":\"string\"}}}"));
+}
+
+#[test]
+fn test_info() {
+    assert_eq!(test::NAME, "test");
+    assert_eq!(test::PATH, "/test");
+    assert_eq!(test::METHODS, &["GET"]);
+    assert!(test::GUARDS.is_empty());
+
+    assert_eq!(head_test::METHODS, &["GET", "HEAD"]);
+    assert_eq!(head_test::DOC, "Handler that responds to HEAD.");
+    assert_eq!(head_test::routes(), vec![(String::from("/test"), &["GET", "HEAD"][..], "head_test")]);
+}
+
+#[cfg(feature = "runtime")]
+#[test]
+fn test_route_info_trait() {
+    use actix_web_cute_runtime::RouteInfo;
+
+    fn info<R: RouteInfo>() -> (&'static str, &'static str, &'static [&'static str], &'static str) {
+        (R::NAME, R::PATH, R::METHODS, R::DOC)
+    }
+
+    assert_eq!(info::<head_test>(), ("head_test", "/test", &["GET", "HEAD"][..], "Handler that responds to HEAD."));
+}
+
+#[test]
+fn test_hook() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(hook_test)));
+
+    let request = srv.request(http::Method::GET, srv.url("/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+}
+
+#[test]
+fn test_head() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(head_test)));
+
+    let request = srv.request(http::Method::GET, srv.url("/test"));
+    let mut response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+    let body = srv.block_on(response.body()).unwrap();
+    assert_eq!(&body[..], b"body");
+
+    let request = srv.request(http::Method::HEAD, srv.url("/test"));
+    let mut response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+    let body = srv.block_on(response.body()).unwrap();
+    assert!(body.is_empty());
+
+    let request = srv.request(http::Method::POST, srv.url("/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_client_error());
+}
@@ -1,10 +1,10 @@
-use actix_web_cute_codegen::{get, hook, scope};
+use actix_web_cute_codegen::{get, post, hook, scope};
 use actix_http::HttpService;
 use actix_http_test::TestServer;
-use actix_web::{http, App, HttpResponse, Responder};
+use actix_web::{http, web, App, HttpResponse, Responder};
 use futures::{Future, future};
 
-use std::sync::atomic::{self, AtomicBool};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
 
 #[get("/outer_test")]
 pub fn outer_test() -> impl Responder {
@@ -21,7 +21,7 @@ pub fn outer_init_scope<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Sc
     scope
 }
 
-#[scope("/scope", hook="outer_init_scope", handler="outer_test")]
+#[scope("/scope", magic_names, hook="outer_init_scope", handler="outer_test", scope="data_inner")]
 const mod_inner: () = {
     use super::*;
 
@@ -35,7 +35,7 @@ const mod_inner: () = {
         future::ok(HttpResponse::Ok().finish())
     }
 
-    //Special member to act as hook
+    //Special member to act as hook with magic_names
     pub fn init<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
         INIT.store(true, atomic::Ordering::Relaxed);
         scope
@@ -47,6 +47,7 @@ const mod_inner: () = {
         scope
     }
 
+    #[allow(dead_code)]
     pub fn init_scope_unused<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
         NOT_USED_HOOK_INIT.store(true, atomic::Ordering::Relaxed);
         scope
@@ -63,6 +64,366 @@ const mod_inner: () = {
     }
 };
 
+#[scope("/data_scope", data="\"scope data\"")]
+const data_inner: () = {
+    use super::*;
+
+    #[get("/test")]
+    pub fn test(data: web::Data<&'static str>) -> impl Responder {
+        match *data.get_ref() {
+            "scope data" => HttpResponse::Ok(),
+            _ => HttpResponse::InternalServerError(),
+        }
+    }
+
+    #[get("/shared", data="1usize")]
+    pub fn shared_get(data: web::Data<usize>) -> String {
+        data.get_ref().to_string()
+    }
+
+    #[post("/shared", data="2usize")]
+    pub fn shared_post(data: web::Data<usize>) -> String {
+        data.get_ref().to_string()
+    }
+};
+
+#[scope("/methods_scope", options, auto_head, strict, smoke_test)]
+mod methods_inner {
+    use super::*;
+
+    #[get("/items")]
+    pub fn get_items() -> impl Responder {
+        HttpResponse::Ok()
+    }
+
+    #[post("/items")]
+    fn post_items() -> impl Responder {
+        HttpResponse::Created()
+    }
+}
+
+#[test]
+fn test_methods_inner() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(methods_inner::scope)));
+
+    let request = srv.request(http::Method::GET, srv.url("/methods_scope/items"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = srv.request(http::Method::HEAD, srv.url("/methods_scope/items"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = srv.request(http::Method::POST, srv.url("/methods_scope/items"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::CREATED);
+
+    let request = srv.request(http::Method::PUT, srv.url("/methods_scope/items"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers().get(http::header::ALLOW).unwrap(), "GET, HEAD, POST, OPTIONS");
+
+    let request = srv.request(http::Method::OPTIONS, srv.url("/methods_scope/items"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert_eq!(response.headers().get(http::header::ALLOW).unwrap(), "GET, HEAD, POST, OPTIONS");
+}
+
+#[scope("/catch_all_scope", strict)]
+mod catch_all_inner {
+    use super::*;
+
+    #[get("/{id}/{tail:.*}")]
+    pub fn file() -> impl Responder {
+        HttpResponse::Ok()
+    }
+
+    #[get("/items")]
+    pub fn items() -> impl Responder {
+        HttpResponse::Accepted()
+    }
+}
+
+#[test]
+fn test_catch_all_inner() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(catch_all_inner::scope)));
+
+    let request = srv.request(http::Method::GET, srv.url("/catch_all_scope/1/logo.png"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = srv.request(http::Method::GET, srv.url("/catch_all_scope/items"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::ACCEPTED);
+}
+
+#[scope("/guarded_scope")]
+mod guarded_inner {
+    use super::*;
+
+    pub fn is_admin(head: &actix_web::dev::RequestHead) -> bool {
+        head.headers.contains_key("x-admin")
+    }
+
+    #[get("/items", guard="is_admin")]
+    pub fn get_items() -> impl Responder {
+        HttpResponse::Ok()
+    }
+
+    #[post("/items")]
+    pub fn post_items() -> impl Responder {
+        HttpResponse::Created()
+    }
+
+    #[get("/admin", guard="is_admin")]
+    pub fn admin() -> impl Responder {
+        HttpResponse::Ok()
+    }
+}
+
+#[test]
+fn test_guarded_inner() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(guarded_inner::scope)));
+
+    let request = srv.request(http::Method::GET, srv.url("/guarded_scope/items")).header("x-admin", "1");
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = srv.request(http::Method::POST, srv.url("/guarded_scope/items")).header("x-admin", "1");
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::CREATED);
+
+    let request = srv.request(http::Method::GET, srv.url("/guarded_scope/items"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers().get(http::header::ALLOW).unwrap(), "POST");
+
+    let request = srv.request(http::Method::GET, srv.url("/guarded_scope/admin"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
+
+#[scope("/outer", lock="tests/routes.lock", smoke_test)]
+mod nested_outer {
+    use super::*;
+
+    #[get("/test")]
+    pub fn test() -> impl Responder {
+        HttpResponse::Ok()
+    }
+
+    #[default_service]
+    pub fn fallback<P: 'static>() -> impl actix_web::dev::HttpServiceFactory<P> {
+        web::resource("/{tail:.*}").to(|| HttpResponse::Accepted())
+    }
+
+    #[hook(after_services)]
+    pub fn late<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
+        scope.route("/late", web::get().to(|| HttpResponse::Ok()))
+    }
+
+    #[scope("/inner")]
+    mod nested_inner {
+        use super::*;
+
+        #[get("/test")]
+        pub fn test() -> impl Responder {
+            HttpResponse::Ok()
+        }
+    }
+}
+
+static HOOK_ORDER: AtomicUsize = AtomicUsize::new(0);
+static CONFIG_HOOK: AtomicUsize = AtomicUsize::new(0);
+
+pub fn outer_resource_hook<P: 'static>(resource: actix_web::Resource<P>) -> actix_web::Resource<P> {
+    resource.data(24usize)
+}
+
+#[scope("/hooks")]
+mod hooks_inner {
+    use super::*;
+
+    pub fn resource_hook<P: 'static>(resource: actix_web::Resource<P>) -> actix_web::Resource<P> {
+        resource.data(42usize)
+    }
+
+    #[get("/test", hook="resource_hook")]
+    pub fn test(data: web::Data<usize>) -> impl Responder {
+        match *data.get_ref() {
+            42 => HttpResponse::Ok(),
+            _ => HttpResponse::InternalServerError(),
+        }
+    }
+
+    #[get("/outer", hook="super::outer_resource_hook")]
+    pub fn outer(data: web::Data<usize>) -> impl Responder {
+        match *data.get_ref() {
+            24 => HttpResponse::Ok(),
+            _ => HttpResponse::InternalServerError(),
+        }
+    }
+
+    #[hook(order = 2)]
+    pub fn second<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
+        assert_eq!(HOOK_ORDER.swap(2, atomic::Ordering::Relaxed), 1);
+        scope
+    }
+
+    #[hook(order = 1)]
+    pub fn first<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
+        assert_eq!(HOOK_ORDER.swap(1, atomic::Ordering::Relaxed), 0);
+        scope
+    }
+
+    #[hook(after_services)]
+    pub fn last<P: 'static>(scope: actix_web::Scope<P>) -> actix_web::Scope<P> {
+        assert_eq!(HOOK_ORDER.swap(3, atomic::Ordering::Relaxed), 2);
+        scope.route("/after", web::get().to(|| HttpResponse::Ok()))
+    }
+
+    #[hook(config)]
+    pub fn before_register<P: 'static>(_config: &mut actix_web::dev::ServiceConfig<P>) {
+        assert_eq!(CONFIG_HOOK.swap(1, atomic::Ordering::Relaxed), 0);
+    }
+
+    #[hook(config, after_services)]
+    pub fn after_register<P: 'static>(_config: &mut actix_web::dev::ServiceConfig<P>) {
+        assert_eq!(CONFIG_HOOK.swap(2, atomic::Ordering::Relaxed), 1);
+    }
+}
+
+#[test]
+fn test_hooks_inner() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(hooks_inner::scope)));
+
+    let request = srv.request(http::Method::GET, srv.url("/hooks/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    let request = srv.request(http::Method::GET, srv.url("/hooks/outer"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    let request = srv.request(http::Method::GET, srv.url("/hooks/after"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    assert_eq!(HOOK_ORDER.load(atomic::Ordering::Relaxed), 3);
+    assert_eq!(CONFIG_HOOK.load(atomic::Ordering::Relaxed), 2);
+}
+
+#[scope("/aliases", docs="/docs")]
+mod aliases_inner {
+    use actix_web_cute_codegen::get as http_get;
+    use super::{HttpResponse, Responder};
+
+    #[http_get("/test")]
+    pub fn test() -> impl Responder {
+        HttpResponse::Ok()
+    }
+
+    #[actix_web_cute_codegen::post("/test")]
+    pub fn test_post() -> impl Responder {
+        HttpResponse::Accepted()
+    }
+}
+
+#[test]
+fn test_aliases_inner() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(aliases_inner::scope)));
+
+    let request = srv.request(http::Method::GET, srv.url("/aliases/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = srv.request(http::Method::POST, srv.url("/aliases/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::ACCEPTED);
+}
+
+#[test]
+fn test_docs_inner() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(aliases_inner::scope)));
+
+    let request = srv.request(http::Method::GET, srv.url("/aliases/docs/openapi.json"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(response.headers().get(http::header::CONTENT_TYPE).unwrap(), "application/json");
+
+    let request = srv.request(http::Method::GET, srv.url("/aliases/docs"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(response.headers().get(http::header::CONTENT_TYPE).unwrap(), "text/html; charset=utf-8");
+
+    let document = aliases_inner::scope::openapi();
+    assert!(document.contains("\"/aliases/test\":{\"get\""));
+    assert!(!document.contains("/aliases/docs"));
+}
+
+#[test]
+fn test_nested_routes() {
+    let routes = nested_outer::scope::routes();
+    let paths = routes.iter().map(|(path, _, _)| path.as_str()).collect::<Vec<_>>();
+
+    assert_eq!(paths, vec!["/outer/test", "/outer/inner/test"]);
+}
+
+#[cfg(feature = "runtime")]
+#[test]
+fn test_scope_info_trait() {
+    use actix_web_cute_runtime::{RouteInfo, ScopeInfo};
+
+    assert_eq!(<nested_outer::scope as ScopeInfo>::routes(), nested_outer::scope::routes());
+    //Route services of scope's members are kept
+    assert_eq!(<nested_outer::test as RouteInfo>::PATH, "/test");
+}
+
+#[test]
+fn test_nested_scope() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(nested_outer::scope)));
+
+    let request = srv.request(http::Method::GET, srv.url("/outer/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    let request = srv.request(http::Method::GET, srv.url("/outer/inner/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    let request = srv.request(http::Method::GET, srv.url("/outer/late"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = srv.request(http::Method::GET, srv.url("/outer/missing"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::ACCEPTED);
+
+    let request = srv.request(http::Method::GET, srv.url("/inner/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_client_error());
+}
+
+#[test]
+fn test_data_inner() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(data_inner)));
+
+    let request = srv.request(http::Method::GET, srv.url("/data_scope/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
+    //Routes of the same path keep their own data
+    let request = srv.request(http::Method::GET, srv.url("/data_scope/shared"));
+    let mut response = srv.block_on(request.send()).unwrap();
+    let body = srv.block_on(response.body()).unwrap();
+    assert_eq!(&body[..], b"1");
+
+    let request = srv.request(http::Method::POST, srv.url("/data_scope/shared"));
+    let mut response = srv.block_on(request.send()).unwrap();
+    let body = srv.block_on(response.body()).unwrap();
+    assert_eq!(&body[..], b"2");
+}
+
 #[test]
 fn test_mod_inner() {
     let mut srv = TestServer::new(|| HttpService::new(App::new().service(mod_inner)));
@@ -75,6 +436,10 @@ fn test_mod_inner() {
     let response = srv.block_on(request.send()).unwrap();
     assert!(response.status().is_success());
 
+    let request = srv.request(http::Method::GET, srv.url("/scope/data_scope/test"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+
     let request = srv.request(http::Method::GET, srv.url("/scope/test_async"));
     let response = srv.block_on(request.send()).unwrap();
     assert!(response.status().is_success());
@@ -92,3 +457,77 @@ fn test_mod_inner() {
     assert!(HOOK_INIT.load(atomic::Ordering::Relaxed));
     assert!(!NOT_USED_HOOK_INIT.load(atomic::Ordering::Relaxed));
 }
+
+#[scope("/client", client)]
+mod client_inner {
+    use super::*;
+
+    #[get("/greet/{name}")]
+    pub fn greet(name: web::Path<String>) -> String {
+        format!("Hello {}", *name)
+    }
+
+    #[post("/sum/{base}")]
+    pub fn sum(base: web::Path<u32>, numbers: web::Json<Vec<u32>>) -> web::Json<u32> {
+        web::Json(numbers.iter().sum::<u32>() + *base)
+    }
+}
+
+#[test]
+fn test_client_inner() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(client_inner::scope)));
+    let client = client_inner::scope::client(&srv.url("/"));
+
+    let (status, body) = srv.block_on(client.greet("cute".to_owned())).unwrap();
+    assert!(status.is_success());
+    assert_eq!(body, b"Hello cute");
+
+    let sum = srv.block_on(client.sum(1, &vec![2, 3])).unwrap();
+    assert_eq!(sum, 6);
+}
+
+#[scope("/client/{group}", client)]
+mod client_group_inner {
+    use super::*;
+
+    #[get("/echo/{text}")]
+    pub fn echo(path: web::Path<(String, String)>) -> web::Json<String> {
+        web::Json(format!("{}:{}", path.0, path.1))
+    }
+}
+
+#[test]
+fn test_client_group_inner() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(client_group_inner::scope)));
+    let client = client_group_inner::scope::client(&srv.url("/"));
+
+    let text = srv.block_on(client.echo("cute group", "a b?c#d")).unwrap();
+    assert_eq!(text, "cute group:a b?c#d");
+}
+
+#[scope("/shadowed_scope", smoke_test)]
+mod shadowed_inner {
+    use super::*;
+
+    #[get("/{id}")]
+    pub fn item() -> impl Responder {
+        HttpResponse::Ok()
+    }
+
+    #[get("/items")]
+    pub fn items() -> impl Responder {
+        HttpResponse::Ok()
+    }
+}
+
+#[test]
+fn test_smoke_scopes() {
+    methods_inner::scope::smoke_test();
+    nested_outer::scope::smoke_test();
+}
+
+#[test]
+#[should_panic(expected = "GET /shadowed_scope/items of 'items' reaches 'item'")]
+fn test_smoke_shadowed_scope() {
+    shadowed_inner::scope::smoke_test();
+}
@@ -0,0 +1,98 @@
+use actix_web_cute_codegen::{get, post, scope};
+use actix_http::HttpService;
+use actix_http_test::TestServer;
+use actix_web::{http, web, App};
+
+#[scope("/users", client)]
+pub trait UsersApi {
+    ///Returns user's name.
+    #[get("/{id}")]
+    fn get(&self, id: web::Path<u32>) -> web::Json<String>;
+
+    #[post("/{id}/rename")]
+    fn rename(&self, id: web::Path<u32>, name: web::Json<String>) -> web::Json<String>;
+}
+
+pub struct Users {
+    prefix: String,
+}
+
+impl UsersApi for Users {
+    fn get(&self, id: web::Path<u32>) -> web::Json<String> {
+        web::Json(format!("{}{}", self.prefix, *id))
+    }
+
+    fn rename(&self, id: web::Path<u32>, name: web::Json<String>) -> web::Json<String> {
+        web::Json(format!("{}{}", *name, *id))
+    }
+}
+
+#[scope("/static", path_env="STATIC_API_PATH")]
+trait StaticApi {
+    #[get("/hello")]
+    fn hello() -> String;
+}
+
+struct Static;
+
+impl StaticApi for Static {
+    fn hello() -> String {
+        "hello".to_owned()
+    }
+}
+
+pub fn is_admin(head: &actix_web::dev::RequestHead) -> bool {
+    head.headers.contains_key("x-admin")
+}
+
+#[scope("/admin")]
+trait AdminApi {
+    #[get("/stats", guard="is_admin")]
+    fn stats() -> String;
+}
+
+impl AdminApi for Static {
+    fn stats() -> String {
+        "stats".to_owned()
+    }
+}
+
+#[test]
+fn test_trait_scope() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(UsersApiScope(Users { prefix: "user".to_owned() })).service(StaticApiScope(Static))));
+    let client = UsersApiClient::new(&srv.url("/"));
+    let client: &dyn UsersApiAsync = &client;
+
+    let name = srv.block_on(client.get(1)).unwrap();
+    assert_eq!(name, "user1");
+
+    let name = srv.block_on(client.rename(2, &"cute".to_owned())).unwrap();
+    assert_eq!(name, "cute2");
+
+    let request = srv.request(http::Method::GET, srv.url("/static/hello"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+}
+
+#[test]
+fn test_trait_scope_guard() {
+    let mut srv = TestServer::new(|| HttpService::new(App::new().service(AdminApiScope(Static))));
+
+    let request = srv.request(http::Method::GET, srv.url("/admin/stats"));
+    let response = srv.block_on(request.send()).unwrap();
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+
+    let request = srv.request(http::Method::GET, srv.url("/admin/stats")).header("x-admin", "1");
+    let response = srv.block_on(request.send()).unwrap();
+    assert!(response.status().is_success());
+}
+
+#[test]
+fn test_trait_scope_routes() {
+    let routes = UsersApiScope::<Users>::routes();
+    let paths = routes.iter().map(|(path, methods, _)| (path.as_str(), *methods)).collect::<Vec<_>>();
+
+    assert_eq!(paths, vec![("/users/{id}", &["GET"][..]), ("/users/{id}/rename", &["POST"][..])]);
+    #[cfg(feature = "runtime")]
+    assert_eq!(<UsersApiScope<Users> as actix_web_cute_runtime::ScopeInfo>::routes(), routes);
+}